@@ -1,6 +1,6 @@
 use structopt::StructOpt;
 
-use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::diagnostic::{Applicability, Diagnostic, Label, Suggestion};
 use codespan_reporting::files::SimpleFiles;
 use codespan_reporting::term::termcolor::StandardStream;
 use codespan_reporting::term::{self, ColorArg};
@@ -93,7 +93,10 @@ fn main() {
             ])
             .with_notes(vec![
                 "there is a builtin with a similar name: `NATURAL`".to_owned()
-            ]),
+            ])
+            .with_suggestions(vec![Suggestion::new(file_id1, 96..102, "NATURAL")
+                .with_message("use the builtin with a similar name")
+                .with_applicability(Applicability::MachineApplicable)]),
         // Unused parameter warning
         Diagnostic::warning()
             .with_message("unused parameter pattern: `n₂`")
@@ -153,6 +156,6 @@ fn main() {
     let writer = StandardStream::stderr(opts.color.into());
     let config = codespan_reporting::term::Config::default();
     for diagnostic in &diagnostics {
-        term::emit(&mut writer.lock(), &config, &files, &diagnostic).unwrap();
+        term::emit(&mut writer.lock(), &config, &files, diagnostic).unwrap();
     }
 }
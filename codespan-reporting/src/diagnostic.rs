@@ -60,15 +60,16 @@ pub enum LabelStyle {
 }
 
 /// A label describing an underlined region of code associated with a diagnostic.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Label<FileId> {
     /// The style of the label.
     pub style: LabelStyle,
     /// The file that we are labelling.
     pub file_id: FileId,
-    /// The range we are going to include in the final snippet.
-    pub range: Range<usize>,
+    /// The range we are going to include in the final snippet, or `None` if
+    /// the label refers to the file as a whole.
+    pub range: Option<Range<usize>>,
     /// An optional message to provide some additional information for the
     /// underlined code. These should not include line breaks.
     pub message: String,
@@ -84,7 +85,21 @@ impl<FileId> Label<FileId> {
         Label {
             style,
             file_id,
-            range: range.into(),
+            range: Some(range.into()),
+            message: String::new(),
+        }
+    }
+
+    /// Create a new label that refers to a whole file, rather than a range of
+    /// source code within the file.
+    ///
+    /// This is useful for diagnostics that concern an entire file, for example
+    /// when the file is not valid UTF-8, or is missing a module header.
+    pub fn new_file(style: LabelStyle, file_id: FileId) -> Label<FileId> {
+        Label {
+            style,
+            file_id,
+            range: None,
             message: String::new(),
         }
     }
@@ -99,6 +114,16 @@ impl<FileId> Label<FileId> {
         Label::new(LabelStyle::Secondary, file_id, range)
     }
 
+    /// Create a new primary label that refers to a whole file.
+    pub fn primary_file(file_id: FileId) -> Label<FileId> {
+        Label::new_file(LabelStyle::Primary, file_id)
+    }
+
+    /// Create a new secondary label that refers to a whole file.
+    pub fn secondary_file(file_id: FileId) -> Label<FileId> {
+        Label::new_file(LabelStyle::Secondary, file_id)
+    }
+
     /// Add a message to the diagnostic.
     pub fn with_message(mut self, message: impl Into<String>) -> Label<FileId> {
         self.message = message.into();
@@ -106,9 +131,134 @@ impl<FileId> Label<FileId> {
     }
 }
 
+/// A child diagnostic, providing additional information about its parent
+/// diagnostic, with its own severity, message, and source labels.
+///
+/// This is useful for pointing out related locations, for example:
+///
+/// ```text
+/// note: the previous definition is here
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct SubDiagnostic<FileId> {
+    /// The severity of the child diagnostic.
+    pub severity: Severity,
+    /// The message associated with the child diagnostic. This should not
+    /// include line breaks.
+    pub message: String,
+    /// Source labels that the child diagnostic refers to.
+    pub labels: Vec<Label<FileId>>,
+}
+
+impl<FileId> SubDiagnostic<FileId> {
+    /// Create a new child diagnostic.
+    pub fn new(severity: Severity) -> SubDiagnostic<FileId> {
+        SubDiagnostic {
+            severity,
+            message: String::new(),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Create a new child diagnostic with a severity of `Severity::Warning`.
+    pub fn warning() -> SubDiagnostic<FileId> {
+        SubDiagnostic::new(Severity::Warning)
+    }
+
+    /// Create a new child diagnostic with a severity of `Severity::Note`.
+    pub fn note() -> SubDiagnostic<FileId> {
+        SubDiagnostic::new(Severity::Note)
+    }
+
+    /// Create a new child diagnostic with a severity of `Severity::Help`.
+    pub fn help() -> SubDiagnostic<FileId> {
+        SubDiagnostic::new(Severity::Help)
+    }
+
+    /// Add a message to the child diagnostic.
+    pub fn with_message(mut self, message: impl Into<String>) -> SubDiagnostic<FileId> {
+        self.message = message.into();
+        self
+    }
+
+    /// Add some labels to the child diagnostic.
+    pub fn with_labels(mut self, labels: Vec<Label<FileId>>) -> SubDiagnostic<FileId> {
+        self.labels = labels;
+        self
+    }
+}
+
+/// The confidence with which a suggestion can be applied automatically.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended, and can be applied
+    /// to the source code automatically.
+    MachineApplicable,
+    /// The suggestion may be what the user intended, but it is uncertain, so it
+    /// should only be applied after being reviewed.
+    MaybeIncorrect,
+    /// The suggestion contains placeholders like `(...)` or `{ /* fields */ }`
+    /// that must be filled in by the user before it is valid code.
+    HasPlaceholders,
+    /// The applicability of the suggestion is unknown.
+    Unspecified,
+}
+
+/// A suggested edit to the source code, replacing a range of a file with
+/// some new text.
+///
+/// An empty `range` denotes an insertion, and an empty `replacement` denotes
+/// a removal.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Suggestion<FileId> {
+    /// The file that the edit applies to.
+    pub file_id: FileId,
+    /// The range of the source code to be replaced.
+    pub range: Range<usize>,
+    /// The text to replace the range with.
+    pub replacement: String,
+    /// A message describing the edit. This should not include line breaks.
+    pub message: String,
+    /// How confident we are that the edit is correct.
+    pub applicability: Applicability,
+}
+
+impl<FileId> Suggestion<FileId> {
+    /// Create a new suggestion, with an applicability of
+    /// `Applicability::Unspecified`.
+    pub fn new(
+        file_id: FileId,
+        range: impl Into<Range<usize>>,
+        replacement: impl Into<String>,
+    ) -> Suggestion<FileId> {
+        Suggestion {
+            file_id,
+            range: range.into(),
+            replacement: replacement.into(),
+            message: String::new(),
+            applicability: Applicability::Unspecified,
+        }
+    }
+
+    /// Add a message to the suggestion.
+    pub fn with_message(mut self, message: impl Into<String>) -> Suggestion<FileId> {
+        self.message = message.into();
+        self
+    }
+
+    /// Set the applicability of the suggestion.
+    pub fn with_applicability(mut self, applicability: Applicability) -> Suggestion<FileId> {
+        self.applicability = applicability;
+        self
+    }
+}
+
 /// Represents a diagnostic message that can provide information like errors and
 /// warnings to the user.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
 pub struct Diagnostic<FileId> {
     /// The overall severity of the diagnostic
@@ -126,6 +276,10 @@ pub struct Diagnostic<FileId> {
     /// Notes that are associated with the primary cause of the diagnostic.
     /// These can include line breaks for improved formatting.
     pub notes: Vec<String>,
+    /// Child diagnostics that refer to related locations in the source code.
+    pub children: Vec<SubDiagnostic<FileId>>,
+    /// Suggested edits to the source code that would address the diagnostic.
+    pub suggestions: Vec<Suggestion<FileId>>,
 }
 
 impl<FileId> Diagnostic<FileId> {
@@ -137,6 +291,8 @@ impl<FileId> Diagnostic<FileId> {
             message: String::new(),
             labels: Vec::new(),
             notes: Vec::new(),
+            children: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
@@ -188,4 +344,37 @@ impl<FileId> Diagnostic<FileId> {
         self.notes = notes;
         self
     }
+
+    /// Add some child diagnostics to the diagnostic.
+    pub fn with_children(mut self, children: Vec<SubDiagnostic<FileId>>) -> Diagnostic<FileId> {
+        self.children = children;
+        self
+    }
+
+    /// Add some suggested edits to the diagnostic.
+    pub fn with_suggestions(mut self, suggestions: Vec<Suggestion<FileId>>) -> Diagnostic<FileId> {
+        self.suggestions = suggestions;
+        self
+    }
+
+    /// The label that the diagnostic is reported at in formats that only
+    /// have room for one location: its first primary label, or its first
+    /// label if it has no primary labels.
+    pub(crate) fn main_label(&self) -> Option<&Label<FileId>> {
+        self.labels
+            .iter()
+            .find(|label| label.style == LabelStyle::Primary)
+            .or_else(|| self.labels.first())
+    }
+
+    /// The message of the diagnostic, followed by its notes on separate
+    /// lines, for formats that have no room for notes.
+    pub(crate) fn message_with_notes(&self) -> String {
+        let mut message = self.message.clone();
+        for note in &self.notes {
+            message.push('\n');
+            message.push_str(note);
+        }
+        message
+    }
 }
@@ -0,0 +1,63 @@
+//! Escaping of text in XML and HTML markup.
+
+use std::fmt;
+
+/// Text that is escaped when it is written in XML or HTML attributes or
+/// elements.
+///
+/// Line breaks and tabs are written as character references, so that they are
+/// kept in attribute values. Other control characters can't be represented in
+/// XML 1.0, so they are replaced with `U+FFFD`.
+pub struct Escaped<'a>(pub &'a str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ch in self.0.chars() {
+            match ch {
+                '\n' => f.write_str("&#10;")?,
+                '\r' => f.write_str("&#13;")?,
+                '\t' => f.write_str("&#9;")?,
+                '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => f.write_str("\u{fffd}")?,
+                _ => match markup_entity(ch) {
+                    Some(entity) => f.write_str(entity)?,
+                    None => write!(f, "{}", ch)?,
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The entity that a character is written as in XML and HTML markup, if it
+/// has to be escaped.
+pub fn markup_entity(ch: char) -> Option<&'static str> {
+    match ch {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&#39;"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaped_markup() {
+        assert_eq!(
+            Escaped("<a href=\"x\">'&'</a>").to_string(),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;",
+        );
+    }
+
+    #[test]
+    fn escaped_control_characters() {
+        assert_eq!(
+            Escaped("a\tb\r\nc\u{1b}[0m\u{0}").to_string(),
+            "a&#9;b&#13;&#10;c\u{fffd}[0m\u{fffd}",
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Extended explanations of diagnostic codes.
+//!
+//! This is useful for implementing an `--explain` command line option, that
+//! describes the cause of a diagnostic in more detail, along with examples.
+//! The explanations can be rendered to a terminal using
+//! `codespan_reporting::term::emit_explanation`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::explain::Registry;
+//!
+//! let registry = Registry::new().with_explanation(
+//!     "E0001",
+//!     "An unexpected type was found.\n\
+//!      \n\
+//!      ```\n\
+//!      _ = 123 + \"hello\"\n\
+//!      ```\n",
+//! );
+//!
+//! assert!(registry.get("E0001").is_some());
+//! assert!(registry.get("E0002").is_none());
+//! ```
+
+use std::collections::BTreeMap;
+
+/// A registry mapping diagnostic codes to their extended explanations.
+///
+/// Explanations are written in a subset of Markdown, supporting `#` headings,
+/// fenced code blocks, and inline code spans.
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    explanations: BTreeMap<String, String>,
+}
+
+impl Registry {
+    /// Create a new, empty registry.
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Add an explanation for a diagnostic code, replacing any existing
+    /// explanation for that code.
+    pub fn add(&mut self, code: impl Into<String>, explanation: impl Into<String>) {
+        self.explanations.insert(code.into(), explanation.into());
+    }
+
+    /// Add an explanation for a diagnostic code, returning the registry.
+    pub fn with_explanation(
+        mut self,
+        code: impl Into<String>,
+        explanation: impl Into<String>,
+    ) -> Registry {
+        self.add(code, explanation);
+        self
+    }
+
+    /// The explanation of the given diagnostic code.
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.explanations.get(code).map(String::as_str)
+    }
+
+    /// The codes that have explanations, in sorted order.
+    pub fn codes(&self) -> impl Iterator<Item = &str> {
+        self.explanations.keys().map(String::as_str)
+    }
+}
@@ -42,20 +42,47 @@ where
     /// assert_eq!(line.column_index(line.start + line.source.len()), 3);
     /// ```
     pub fn column_index(&self, byte_index: usize) -> usize {
+        self.column_index_in(byte_index, ColumnUnit::Char)
+    }
+
+    /// The column index at the given byte index in the source file, counted
+    /// in the given unit. Byte indices that fall inside a character or
+    /// grapheme cluster are counted as the start of it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use codespan_reporting::files::{ColumnUnit, Line};
+    ///
+    /// let line = Line {
+    ///     start: 0,
+    ///     number: 1,
+    ///     source: "cafe\u{301} 👩‍🔬!",
+    /// };
+    ///
+    /// assert_eq!(line.column_index_in(7, ColumnUnit::Char), 6);
+    /// assert_eq!(line.column_index_in(7, ColumnUnit::Grapheme), 5);
+    /// assert_eq!(line.column_index_in(18, ColumnUnit::Char), 9);
+    /// assert_eq!(line.column_index_in(18, ColumnUnit::Grapheme), 6);
+    /// ```
+    pub fn column_index_in(&self, byte_index: usize, unit: ColumnUnit) -> usize {
+        use unicode_segmentation::UnicodeSegmentation;
+
         match byte_index.checked_sub(self.start) {
             None => 0,
             Some(relative_index) => {
                 let line_source = self.source.as_ref();
-                let column_index = line_source
-                    .char_indices()
-                    .map(|(i, _)| i)
-                    .take_while(|i| *i < relative_index)
-                    .count();
-
-                match () {
-                    () if relative_index >= line_source.len() => column_index,
-                    () if line_source.is_char_boundary(relative_index) => column_index,
-                    () => column_index - 1,
+                match unit {
+                    ColumnUnit::Char => count_columns(
+                        line_source.char_indices().map(|(i, _)| i),
+                        relative_index,
+                        line_source.len(),
+                    ),
+                    ColumnUnit::Grapheme => count_columns(
+                        line_source.grapheme_indices(true).map(|(i, _)| i),
+                        relative_index,
+                        line_source.len(),
+                    ),
                 }
             }
         }
@@ -84,6 +111,47 @@ where
     pub fn column_number(&self, byte_index: usize) -> usize {
         self.column_index(byte_index) + 1
     }
+
+    /// The 1-indexed column number at the given byte index, counted in the
+    /// given unit.
+    pub fn column_number_in(&self, byte_index: usize, unit: ColumnUnit) -> usize {
+        self.column_index_in(byte_index, unit) + 1
+    }
+}
+
+/// The unit that columns are counted in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColumnUnit {
+    /// Count Unicode scalar values, as returned by `str::chars`.
+    Char,
+    /// Count extended grapheme clusters, which are closer to the characters
+    /// perceived by readers. For example `"e\u{301}"` is a single grapheme
+    /// cluster, displayed as `é`.
+    Grapheme,
+}
+
+/// Count the columns that start before a byte index relative to the start of
+/// a line, given the byte indices where each column starts.
+fn count_columns(
+    column_starts: impl Iterator<Item = usize>,
+    relative_index: usize,
+    len: usize,
+) -> usize {
+    let mut column_index = 0;
+    let mut is_boundary = relative_index >= len;
+    for start in column_starts.take_while(|start| *start <= relative_index) {
+        if start == relative_index {
+            is_boundary = true;
+        } else {
+            column_index += 1;
+        }
+    }
+
+    if is_boundary {
+        column_index
+    } else {
+        column_index - 1
+    }
 }
 
 /// Files that can be used for pretty printing.
@@ -106,6 +174,19 @@ pub trait Files<'a> {
     fn line_index(&'a self, id: Self::FileId, byte_index: usize) -> Option<usize>;
 }
 
+/// The line and column numbers of a byte index in a file, which must be in
+/// the files.
+pub(crate) fn position<'a, F: Files<'a>>(
+    files: &'a F,
+    file_id: F::FileId,
+    byte_index: usize,
+) -> (usize, usize) {
+    let line_index = files.line_index(file_id, byte_index);
+    let line = files.line(file_id, line_index.expect("line_index"));
+    let line = line.expect("line");
+    (line.number, line.column_number(byte_index))
+}
+
 /// A single source file.
 ///
 /// This is useful for simple language tests, but it might be worth creating a
@@ -261,6 +342,16 @@ where
     }
 }
 
+impl<Origin, Source> Default for SimpleFiles<Origin, Source>
+where
+    Origin: std::fmt::Display,
+    Source: AsRef<str>,
+{
+    fn default() -> SimpleFiles<Origin, Source> {
+        SimpleFiles::new()
+    }
+}
+
 impl<'a, Origin, Source> Files<'a> for SimpleFiles<Origin, Source>
 where
     Origin: 'a + std::fmt::Display + Clone,
@@ -0,0 +1,324 @@
+//! Automatic application of suggested edits to source files.
+//!
+//! This is useful for implementing a `--fix` mode, where the suggestions made
+//! by diagnostics are written back to the source code.
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Applicability, Diagnostic, Suggestion};
+//! use codespan_reporting::files::SimpleFiles;
+//! use codespan_reporting::fix;
+//!
+//! let mut files = SimpleFiles::new();
+//! let file_id = files.add("test", "let x = 1;;");
+//!
+//! let diagnostics = vec![Diagnostic::warning()
+//!     .with_message("unnecessary trailing semicolon")
+//!     .with_suggestions(vec![Suggestion::new(file_id, 10..11, "")
+//!         .with_applicability(Applicability::MachineApplicable)])];
+//!
+//! let fixes = fix::apply_suggestions(&files, &diagnostics);
+//!
+//! assert_eq!(fixes.source(file_id), Some("let x = 1;"));
+//! assert!(fixes.skipped.is_empty());
+//! ```
+
+use std::ops::Range;
+
+use crate::diagnostic::{Applicability, Diagnostic, Suggestion};
+use crate::files::Files;
+
+/// The reason why a suggestion was not applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The suggestion was not marked as `Applicability::MachineApplicable`.
+    NotMachineApplicable(Applicability),
+    /// The suggestion is identical to one that was already applied.
+    Duplicate,
+    /// The suggestion overlaps with the range of a suggestion that was already
+    /// applied to the same file.
+    Conflict(Range<usize>),
+    /// The range of the suggestion is out of bounds, or does not fall on
+    /// character boundaries in the source file.
+    InvalidRange,
+}
+
+/// A suggestion that was not applied.
+#[derive(Clone, Debug)]
+pub struct SkippedSuggestion<'a, FileId> {
+    /// The suggestion that was skipped.
+    pub suggestion: &'a Suggestion<FileId>,
+    /// The reason why the suggestion was skipped.
+    pub reason: SkipReason,
+}
+
+/// The rewritten source code of a file.
+#[derive(Clone, Debug)]
+pub struct FixedSource<FileId> {
+    /// The file that was rewritten.
+    pub file_id: FileId,
+    /// The source code of the file, with the suggestions applied.
+    pub source: String,
+    /// The number of suggestions that were applied to the file.
+    pub applied: usize,
+}
+
+/// The result of applying suggestions to a set of files.
+#[derive(Clone, Debug)]
+pub struct Fixes<'a, FileId> {
+    /// The rewritten files, in the order that they were first referred to
+    /// by the suggestions. Files without any applied suggestions are omitted.
+    pub files: Vec<FixedSource<FileId>>,
+    /// The suggestions that were not applied, in the order that they were
+    /// encountered.
+    pub skipped: Vec<SkippedSuggestion<'a, FileId>>,
+}
+
+impl<'a, FileId> Fixes<'a, FileId>
+where
+    FileId: Copy + PartialEq,
+{
+    /// The rewritten source code of the given file, if any suggestions were
+    /// applied to it.
+    pub fn source(&self, file_id: FileId) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|fixed| fixed.file_id == file_id)
+            .map(|fixed| fixed.source.as_str())
+    }
+}
+
+/// Apply the machine-applicable suggestions of the given diagnostics to the
+/// source files they refer to.
+///
+/// Suggestions are considered in the order that they appear in the
+/// diagnostics. A suggestion is skipped if it is not machine-applicable, if
+/// its range is invalid, or if it overlaps with a suggestion that was
+/// accepted earlier. Insertions at the same position as another edit are
+/// also considered to be conflicting, as the order of the resulting text
+/// would be ambiguous.
+///
+/// The accepted edits are applied from the back of each file to the front,
+/// so that the byte ranges of the remaining edits stay valid.
+pub fn apply_suggestions<'a, 'files, F>(
+    files: &'files F,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic<F::FileId>>,
+) -> Fixes<'a, F::FileId>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    let mut skipped = Vec::new();
+
+    // Group the candidate suggestions by file
+
+    let mut groups = Vec::new();
+
+    for diagnostic in diagnostics {
+        for suggestion in &diagnostic.suggestions {
+            if suggestion.applicability != Applicability::MachineApplicable {
+                skipped.push(SkippedSuggestion {
+                    suggestion,
+                    reason: SkipReason::NotMachineApplicable(suggestion.applicability),
+                });
+                continue;
+            }
+
+            match groups
+                .iter_mut()
+                .find(|(file_id, _)| suggestion.file_id == *file_id)
+            {
+                None => groups.push((suggestion.file_id, vec![suggestion])),
+                Some((_, suggestions)) => suggestions.push(suggestion),
+            }
+        }
+    }
+
+    // Apply the accepted suggestions to each file
+
+    let mut fixed_sources = Vec::new();
+
+    for (file_id, suggestions) in groups {
+        let mut source = file_source(files, file_id).unwrap_or_default();
+        let mut accepted = Vec::new();
+
+        for suggestion in suggestions {
+            match skip_reason(&source, &accepted, suggestion) {
+                None => accepted.push(suggestion),
+                Some(reason) => skipped.push(SkippedSuggestion { suggestion, reason }),
+            }
+        }
+
+        if accepted.is_empty() {
+            continue;
+        }
+
+        // Apply the edits back-to-front so that the earlier ranges remain valid
+        accepted.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.range.start));
+        for suggestion in &accepted {
+            source.replace_range(suggestion.range.clone(), &suggestion.replacement);
+        }
+
+        fixed_sources.push(FixedSource {
+            file_id,
+            source,
+            applied: accepted.len(),
+        });
+    }
+
+    Fixes {
+        files: fixed_sources,
+        skipped,
+    }
+}
+
+/// Reconstruct the full source of a file by concatenating its lines.
+fn file_source<'files, F: Files<'files>>(files: &'files F, file_id: F::FileId) -> Option<String> {
+    let mut source = String::new();
+    let mut line_index = 0;
+
+    while let Some(line) = files.line(file_id, line_index) {
+        source.push_str(line.source.as_ref());
+        line_index += 1;
+    }
+
+    match line_index {
+        0 => None,
+        _ => Some(source),
+    }
+}
+
+/// Determine whether a suggestion should be skipped, given the suggestions
+/// that have already been accepted for the file.
+fn skip_reason<FileId>(
+    source: &str,
+    accepted: &[&Suggestion<FileId>],
+    suggestion: &Suggestion<FileId>,
+) -> Option<SkipReason> {
+    let range = &suggestion.range;
+
+    if !is_valid_range(source, range) {
+        return Some(SkipReason::InvalidRange);
+    }
+
+    accepted
+        .iter()
+        .find(|other| conflicts(&other.range, range))
+        .map(|other| {
+            if other.range == *range && other.replacement == suggestion.replacement {
+                SkipReason::Duplicate
+            } else {
+                SkipReason::Conflict(other.range.clone())
+            }
+        })
+}
+
+/// Check that a range lies within the source, on character boundaries.
+fn is_valid_range(source: &str, range: &Range<usize>) -> bool {
+    range.start <= range.end
+        && range.end <= source.len()
+        && source.is_char_boundary(range.start)
+        && source.is_char_boundary(range.end)
+}
+
+/// Check if two edits overlap, or if they start at the same position. Edits
+/// that merely touch at their boundaries do not conflict.
+fn conflicts(range0: &Range<usize>, range1: &Range<usize>) -> bool {
+    (range0.start < range1.end && range1.start < range0.end) || range0.start == range1.start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::files::SimpleFiles;
+
+    fn machine_applicable(file_id: usize, range: Range<usize>, text: &str) -> Suggestion<usize> {
+        Suggestion::new(file_id, range, text).with_applicability(Applicability::MachineApplicable)
+    }
+
+    #[test]
+    fn applies_edits_back_to_front() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test", "let x = foo(a, b);\nlet y = x;\n");
+
+        let diagnostics = vec![
+            Diagnostic::error().with_suggestions(vec![machine_applicable(file_id, 8..11, "bar")]),
+            Diagnostic::error().with_suggestions(vec![
+                machine_applicable(file_id, 15..16, "c"),
+                machine_applicable(file_id, 23..23, "mut "),
+            ]),
+        ];
+
+        let fixes = apply_suggestions(&files, &diagnostics);
+
+        assert_eq!(
+            fixes.source(file_id),
+            Some("let x = bar(a, c);\nlet mut y = x;\n")
+        );
+        assert_eq!(fixes.files[0].applied, 3);
+        assert!(fixes.skipped.is_empty());
+    }
+
+    #[test]
+    fn skips_conflicting_edits() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test", "abcdef");
+
+        let diagnostics = vec![Diagnostic::error().with_suggestions(vec![
+            machine_applicable(file_id, 1..4, "X"),
+            machine_applicable(file_id, 3..5, "Y"),
+            machine_applicable(file_id, 1..4, "X"),
+            machine_applicable(file_id, 1..1, "Z"),
+            machine_applicable(file_id, 4..4, "W"),
+        ])];
+
+        let fixes = apply_suggestions(&files, &diagnostics);
+
+        assert_eq!(fixes.source(file_id), Some("aXWef"));
+        let reasons = fixes
+            .skipped
+            .iter()
+            .map(|skipped| skipped.reason.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reasons,
+            [
+                SkipReason::Conflict(1..4),
+                SkipReason::Duplicate,
+                SkipReason::Conflict(1..4),
+            ],
+        );
+    }
+
+    #[test]
+    fn skips_non_machine_applicable_and_invalid_edits() {
+        let mut files = SimpleFiles::new();
+        let file_id0 = files.add("test0", "λx. x");
+        let file_id1 = files.add("test1", "hello");
+
+        let diagnostics = vec![Diagnostic::error().with_suggestions(vec![
+            Suggestion::new(file_id0, 0..2, "\\").with_applicability(Applicability::MaybeIncorrect),
+            machine_applicable(file_id0, 1..2, "y"),
+            machine_applicable(file_id1, 3..9, "p"),
+        ])];
+
+        let fixes = apply_suggestions(&files, &diagnostics);
+
+        assert!(fixes.files.is_empty());
+        let reasons = fixes
+            .skipped
+            .iter()
+            .map(|skipped| skipped.reason.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reasons,
+            [
+                SkipReason::NotMachineApplicable(Applicability::MaybeIncorrect),
+                SkipReason::InvalidRange,
+                SkipReason::InvalidRange,
+            ],
+        );
+    }
+}
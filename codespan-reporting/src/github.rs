@@ -0,0 +1,134 @@
+//! Output as [GitHub Actions workflow commands][commands], which make the
+//! diagnostics show up as annotations on the lines of code they refer to.
+//!
+//! ```text
+//! ::error file=src/main.fun,line=2,col=9,endLine=2,endColumn=11,title=E0001::unexpected type in `+` application
+//! ```
+//!
+//! [commands]: https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Diagnostic, Label};
+//! use codespan_reporting::files::SimpleFiles;
+//! use codespan_reporting::github;
+//!
+//! let mut files = SimpleFiles::new();
+//! let file_id = files.add("src/main.fun", "let x = 1;");
+//!
+//! let diagnostics = vec![Diagnostic::warning()
+//!     .with_code("W0001")
+//!     .with_message("unused variable: `x`")
+//!     .with_labels(vec![Label::primary(file_id, 4..5)])];
+//!
+//! let mut output = Vec::new();
+//! github::emit(&mut output, &files, &diagnostics).unwrap();
+//!
+//! assert_eq!(
+//!     String::from_utf8(output).unwrap(),
+//!     "::warning file=src/main.fun,line=1,col=5,endLine=1,endColumn=6,title=W0001::unused variable: `x`\n",
+//! );
+//! ```
+
+use std::io;
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::files::{self, Files};
+
+/// Emit a batch of diagnostics as workflow commands, using the given writer
+/// and files.
+///
+/// A command is written for each diagnostic, at its first primary label, or
+/// its first label if it has no primary labels. The notes of the diagnostic
+/// are appended to the message, on separate lines.
+pub fn emit<'a, 'files, F>(
+    writer: &mut (impl io::Write + ?Sized),
+    files: &'files F,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic<F::FileId>>,
+) -> io::Result<()>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    for diagnostic in diagnostics {
+        emit_command(writer, files, diagnostic)?;
+    }
+
+    Ok(())
+}
+
+fn emit_command<'files, F: Files<'files>>(
+    writer: &mut (impl io::Write + ?Sized),
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+) -> io::Result<()> {
+    let mut properties = Vec::new();
+    if let Some(label) = diagnostic.main_label() {
+        let origin = files.origin(label.file_id).expect("origin");
+        properties.push(("file", origin.to_string()));
+        if let Some(range) = label.range.as_ref() {
+            let position = |byte_index| files::position(files, label.file_id, byte_index);
+            let (line_number, column_number) = position(range.start);
+            let (end_line_number, end_column_number) = position(range.end);
+            properties.push(("line", line_number.to_string()));
+            properties.push(("col", column_number.to_string()));
+            properties.push(("endLine", end_line_number.to_string()));
+            properties.push(("endColumn", end_column_number.to_string()));
+        }
+    }
+    if let Some(code) = &diagnostic.code {
+        properties.push(("title", code.clone()));
+    }
+
+    write!(writer, "::{}", command(diagnostic.severity))?;
+    for (i, (name, value)) in properties.iter().enumerate() {
+        let separator = if i == 0 { " " } else { "," };
+        write!(writer, "{}{}={}", separator, name, escape_property(value))?;
+    }
+    writeln!(
+        writer,
+        "::{}",
+        escape_data(&diagnostic.message_with_notes())
+    )
+}
+
+/// The workflow command for a severity.
+fn command(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "notice",
+    }
+}
+
+/// Escape the message of a command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape the value of a command property, which is also delimited by `:` and
+/// `,`.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_data_line_breaks() {
+        assert_eq!(
+            escape_data("100% sure\r\nreally"),
+            "100%25 sure%0D%0Areally"
+        );
+    }
+
+    #[test]
+    fn escape_property_delimiters() {
+        assert_eq!(escape_property("C:\\a,b%.fun"), "C%3A\\a%2Cb%25.fun");
+    }
+}
@@ -0,0 +1,200 @@
+//! Output as a [GitLab Code Quality report][code-quality], which shows the
+//! diagnostics in merge requests.
+//!
+//! This module requires the `gitlab` feature to be enabled.
+//!
+//! [code-quality]: https://docs.gitlab.com/ee/ci/testing/code_quality.html#implement-a-custom-tool
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Diagnostic, Label};
+//! use codespan_reporting::files::SimpleFiles;
+//! use codespan_reporting::gitlab;
+//!
+//! let mut files = SimpleFiles::new();
+//! let file_id = files.add("src/main.fun", "let x = 1;");
+//!
+//! let diagnostics = vec![Diagnostic::warning()
+//!     .with_code("W0001")
+//!     .with_message("unused variable: `x`")
+//!     .with_labels(vec![Label::primary(file_id, 4..5)])];
+//!
+//! let mut output = Vec::new();
+//! gitlab::emit(&mut output, &files, &diagnostics).unwrap();
+//! ```
+
+use serde::Serialize;
+use std::io;
+
+use crate::diagnostic::{Diagnostic, Label, Severity};
+use crate::files::Files;
+use crate::term::severity_name;
+
+/// Emit a batch of diagnostics as a Code Quality report, using the given
+/// writer and files.
+///
+/// Each diagnostic is reported at its first primary label, or its first label
+/// if it has no primary labels. Diagnostics without labels are left out, as
+/// the report requires a location for every issue.
+///
+/// The fingerprints of the issues are derived from their code, message, file,
+/// and labelled source code, so they stay the same when the source code
+/// around an issue is edited. Identical issues are told apart by the order in
+/// which they appear.
+pub fn emit<'a, 'files, F>(
+    writer: &mut (impl io::Write + ?Sized),
+    files: &'files F,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic<F::FileId>>,
+) -> io::Result<()>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    let mut fingerprints = Vec::new();
+    let mut issues = Vec::new();
+
+    for diagnostic in diagnostics {
+        let label = match diagnostic.main_label() {
+            Some(label) => label,
+            None => continue,
+        };
+
+        let check_name = match &diagnostic.code {
+            Some(code) => code.clone(),
+            None => severity_name(diagnostic.severity).to_owned(),
+        };
+        let path = files.origin(label.file_id).expect("origin").to_string();
+        let (lines, source) = lines(files, label);
+
+        let mut fingerprint = Fingerprint::new();
+        for part in &[&check_name, &diagnostic.message, &path, &source] {
+            fingerprint.write(part.as_bytes());
+            fingerprint.write(&[0]);
+        }
+        // Tell apart issues that would otherwise have the same fingerprint
+        let occurrences = fingerprints
+            .iter()
+            .filter(|other| **other == fingerprint.finish())
+            .count();
+        fingerprints.push(fingerprint.finish());
+        if occurrences > 0 {
+            fingerprint.write(occurrences.to_string().as_bytes());
+        }
+
+        issues.push(Issue {
+            description: diagnostic.message.clone(),
+            check_name,
+            fingerprint: format!("{:016x}", fingerprint.finish()),
+            severity: severity(diagnostic.severity),
+            location: Location { path, lines },
+        });
+    }
+
+    serde_json::to_writer_pretty(&mut *writer, &issues)?;
+    writeln!(writer)
+}
+
+#[derive(Serialize)]
+struct Issue {
+    description: String,
+    check_name: String,
+    fingerprint: String,
+    severity: &'static str,
+    location: Location,
+}
+
+#[derive(Serialize)]
+struct Location {
+    path: String,
+    lines: Lines,
+}
+
+#[derive(Serialize)]
+struct Lines {
+    begin: usize,
+    end: usize,
+}
+
+/// The lines of a label, and the source code that it labels.
+fn lines<'files, F: Files<'files>>(files: &'files F, label: &Label<F::FileId>) -> (Lines, String) {
+    let range = match label.range.as_ref() {
+        Some(range) => range,
+        None => return (Lines { begin: 1, end: 1 }, String::new()),
+    };
+    let line_index = |byte_index| files.line_index(label.file_id, byte_index);
+    let start_line_index = line_index(range.start).expect("start_line_index");
+    let end_line_index = line_index(range.end).expect("end_line_index");
+    let start_line = files
+        .line(label.file_id, start_line_index)
+        .expect("start_line");
+    let end_line = files.line(label.file_id, end_line_index).expect("end_line");
+
+    // Collect the labelled source from the lines it spans
+    let mut source = String::new();
+    for line_index in start_line_index..=end_line_index {
+        let line = files.line(label.file_id, line_index).expect("line");
+        let line_source = line.source.as_ref();
+        let start = range
+            .start
+            .saturating_sub(line.start)
+            .min(line_source.len());
+        let end = range.end.saturating_sub(line.start).min(line_source.len());
+        source.push_str(&line_source[start..end]);
+    }
+
+    let lines = Lines {
+        begin: start_line.number,
+        end: end_line.number,
+    };
+    (lines, source)
+}
+
+/// The Code Quality severity of a severity.
+fn severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug => "critical",
+        Severity::Error => "major",
+        Severity::Warning => "minor",
+        Severity::Note | Severity::Help => "info",
+    }
+}
+
+/// A 64-bit FNV-1a hash, which unlike the hashers in the standard library is
+/// guaranteed to stay the same between releases.
+struct Fingerprint(u64);
+
+impl Fingerprint {
+    fn new() -> Fingerprint {
+        Fingerprint(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_is_fnv1a() {
+        let fingerprint = |bytes: &[u8]| {
+            let mut fingerprint = Fingerprint::new();
+            fingerprint.write(bytes);
+            fingerprint.finish()
+        };
+
+        assert_eq!(fingerprint(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fingerprint(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fingerprint(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
@@ -0,0 +1,467 @@
+//! HTML back-end for emitting diagnostics.
+//!
+//! Diagnostics are laid out in the same way as on the terminal, inside `<pre>`
+//! elements. Styled text is wrapped in `<span>` elements with CSS classes that
+//! are derived from the names of the fields of [`Styles`], with underscores
+//! replaced by hyphens:
+//!
+//! ```html
+//! <span class="header-error">error[E0001]</span>
+//! ```
+//!
+//! Each diagnostic is rendered in a `<section>` element with an `id`, so that
+//! reports can link to individual diagnostics.
+//!
+//! [`Styles`]: crate::term::Styles
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Diagnostic, Label};
+//! use codespan_reporting::files::SimpleFiles;
+//! use codespan_reporting::html::{self, Config};
+//!
+//! let mut files = SimpleFiles::new();
+//! let file_id = files.add("src/main.fun", "let x = 1 < 2;");
+//!
+//! let diagnostics = vec![Diagnostic::warning()
+//!     .with_message("unused variable: `x`")
+//!     .with_labels(vec![Label::primary(file_id, 4..5)])];
+//!
+//! let mut output = Vec::new();
+//! html::emit(&mut output, &Config::default(), &files, &diagnostics).unwrap();
+//! ```
+
+use std::io;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+use crate::diagnostic::Diagnostic;
+use crate::escape::{markup_entity, Escaped};
+use crate::files::Files;
+use crate::term::{self, Styles};
+
+/// Configures how diagnostics are rendered as HTML.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The config used to lay out the diagnostics. Its styles are used for the
+    /// inline stylesheet.
+    /// Defaults to: `term::Config::default()`.
+    pub term: term::Config,
+    /// Whether to include a `<style>` element with the styles of the term
+    /// config.
+    /// Defaults to: `true`.
+    pub inline_stylesheet: bool,
+    /// The prefix of the `id` of each diagnostic, which is followed by the
+    /// position of the diagnostic, starting at `1`.
+    /// Defaults to: `"diagnostic-"`.
+    pub anchor_prefix: String,
+}
+
+impl Config {
+    /// Create a new config that lays out diagnostics with the given term
+    /// config.
+    pub fn new(term: term::Config) -> Config {
+        Config {
+            term,
+            inline_stylesheet: true,
+            anchor_prefix: "diagnostic-".to_owned(),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::new(term::Config::default())
+    }
+}
+
+/// Emit a batch of diagnostics as an HTML fragment, using the given writer,
+/// config, and files.
+///
+/// The fragment is a `<div class="codespan">` element, preceded by a `<style>`
+/// element if `Config::inline_stylesheet` is enabled.
+pub fn emit<'a, 'files, F>(
+    writer: &mut (impl io::Write + ?Sized),
+    config: &Config,
+    files: &'files F,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic<F::FileId>>,
+) -> io::Result<()>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    if config.inline_stylesheet {
+        writeln!(writer, "<style>")?;
+        write!(writer, "{}", stylesheet(&config.term.styles))?;
+        writeln!(writer, "</style>")?;
+    }
+
+    let term_config = term::Config {
+        styles: class_styles(),
+        ..config.term.clone()
+    };
+
+    writeln!(writer, "<div class=\"codespan\">")?;
+    for (i, diagnostic) in diagnostics.into_iter().enumerate() {
+        writeln!(
+            writer,
+            "<section class=\"diagnostic {severity}\" id=\"{prefix}{number}\">",
+            severity = term::severity_name(diagnostic.severity),
+            prefix = Escaped(&config.anchor_prefix),
+            number = i + 1,
+        )?;
+        write!(writer, "<pre>")?;
+        let mut html_writer = HtmlWriter::new(&mut *writer);
+        term::emit(&mut html_writer, &term_config, files, diagnostic)?;
+        html_writer.finish()?;
+        writeln!(writer, "</pre>")?;
+        writeln!(writer, "</section>")?;
+    }
+    writeln!(writer, "</div>")
+}
+
+/// A stylesheet for the classes of the HTML output, with the colors of the
+/// given styles.
+pub fn stylesheet(styles: &Styles) -> String {
+    let mut stylesheet = String::new();
+    stylesheet.push_str(".codespan pre { font-family: monospace; }\n");
+    stylesheet.push_str(".codespan a { color: inherit; }\n");
+    for name in Styles::NAMES {
+        let spec = styles.by_name(name).expect("style");
+        let declarations = declarations(spec);
+        if !declarations.is_empty() {
+            stylesheet.push_str(&format!(
+                ".codespan .{} {{ {} }}\n",
+                class_name(name),
+                declarations.join(" "),
+            ));
+        }
+    }
+    stylesheet
+}
+
+/// The CSS class of a style, derived from the name of its field in `Styles`.
+fn class_name(name: &str) -> String {
+    name.replace('_', "-")
+}
+
+/// Styles where each field is identified by its position in `Styles::NAMES`,
+/// so that the writer can recover the class of the text it is given.
+fn class_styles() -> Styles {
+    let mut styles = Styles::default();
+    for (i, name) in Styles::NAMES.iter().enumerate() {
+        let spec = styles.by_name_mut(name).expect("style");
+        *spec = ColorSpec::new()
+            .set_fg(Some(Color::Ansi256(i as u8)))
+            .clone();
+    }
+    styles
+}
+
+/// The CSS declarations that correspond to a color spec.
+fn declarations(spec: &ColorSpec) -> Vec<String> {
+    let mut declarations = Vec::new();
+    if let Some(color) = spec.fg().and_then(|color| css_color(color, spec.intense())) {
+        declarations.push(format!("color: {};", color));
+    }
+    if let Some(color) = spec.bg().and_then(|color| css_color(color, false)) {
+        declarations.push(format!("background-color: {};", color));
+    }
+    if spec.bold() {
+        declarations.push("font-weight: bold;".to_owned());
+    }
+    if spec.underline() {
+        declarations.push("text-decoration: underline;".to_owned());
+    }
+    declarations
+}
+
+/// A CSS color for a terminal color, using the VGA palette for the basic
+/// colors.
+fn css_color(color: &Color, intense: bool) -> Option<String> {
+    const NORMAL: [&str; 8] = [
+        "#000000", "#aa0000", "#00aa00", "#aa5500", "#0000aa", "#aa00aa", "#00aaaa", "#aaaaaa",
+    ];
+    const INTENSE: [&str; 8] = [
+        "#555555", "#ff5555", "#55ff55", "#ffff55", "#5555ff", "#ff55ff", "#55ffff", "#ffffff",
+    ];
+
+    let index = match color {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::White => 7,
+        Color::Ansi256(index) => match *index {
+            0..=7 => return Some(NORMAL[*index as usize].to_owned()),
+            8..=15 => return Some(INTENSE[*index as usize - 8].to_owned()),
+            16..=231 => {
+                let level = |n: u8| match n {
+                    0 => 0,
+                    n => 55 + 40 * n,
+                };
+                let index = index - 16;
+                let (r, g, b) = (index / 36, index / 6 % 6, index % 6);
+                let hex = format!("#{:02x}{:02x}{:02x}", level(r), level(g), level(b));
+                return Some(hex);
+            }
+            _ => {
+                let gray = 8 + 10 * (index - 232);
+                return Some(format!("#{:02x}{:02x}{:02x}", gray, gray, gray));
+            }
+        },
+        Color::Rgb(r, g, b) => return Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        _ => return None,
+    };
+
+    if intense {
+        Some(INTENSE[index].to_owned())
+    } else {
+        Some(NORMAL[index].to_owned())
+    }
+}
+
+/// The start of the OSC 8 escape sequences that are used for hyperlinks.
+const HYPERLINK_START: &[u8] = b"\x1b]8;;";
+/// The string terminator that ends an OSC 8 escape sequence.
+const STRING_TERMINATOR: &[u8] = b"\x1b\\";
+
+/// A writer that escapes the text written to it, and turns colors into spans
+/// and terminal hyperlinks into anchors.
+struct HtmlWriter<'a, W: ?Sized> {
+    writer: &'a mut W,
+    /// The bytes of an escape sequence that is being written.
+    escape: Option<Vec<u8>>,
+    is_span_open: bool,
+    is_link_open: bool,
+    /// Whether the open span was opened inside the open link.
+    is_span_in_link: bool,
+}
+
+impl<'a, W: io::Write + ?Sized> HtmlWriter<'a, W> {
+    fn new(writer: &'a mut W) -> HtmlWriter<'a, W> {
+        HtmlWriter {
+            writer,
+            escape: None,
+            is_span_open: false,
+            is_link_open: false,
+            is_span_in_link: false,
+        }
+    }
+
+    /// Write any incomplete escape sequence and close the open elements.
+    fn finish(&mut self) -> io::Result<()> {
+        if let Some(sequence) = self.escape.take() {
+            self.write_text(&sequence)?;
+        }
+        self.close_span()?;
+        self.close_link()
+    }
+
+    fn write_text(&mut self, text: &[u8]) -> io::Result<()> {
+        let mut start = 0;
+        for (i, byte) in text.iter().enumerate() {
+            let entity = match markup_entity(*byte as char) {
+                Some(entity) => entity,
+                None => continue,
+            };
+            self.writer.write_all(&text[start..i])?;
+            self.writer.write_all(entity.as_bytes())?;
+            start = i + 1;
+        }
+        self.writer.write_all(&text[start..])
+    }
+
+    /// Handle the escape sequence that is being written, once it is known
+    /// whether it is a hyperlink.
+    fn continue_escape(&mut self) -> io::Result<()> {
+        let sequence = self.escape.as_ref().expect("escape sequence");
+        if !HYPERLINK_START.starts_with(sequence) && !sequence.starts_with(HYPERLINK_START) {
+            // Not a hyperlink, so write it like any other text
+            let sequence = self.escape.take().expect("escape sequence");
+            self.write_text(&sequence)
+        } else if sequence.len() > HYPERLINK_START.len() && sequence.ends_with(STRING_TERMINATOR) {
+            let sequence = self.escape.take().expect("escape sequence");
+            let url = &sequence[HYPERLINK_START.len()..sequence.len() - STRING_TERMINATOR.len()];
+            self.close_link()?;
+            if !url.is_empty() {
+                self.writer.write_all(b"<a href=\"")?;
+                self.write_text(url)?;
+                self.writer.write_all(b"\">")?;
+                self.is_link_open = true;
+            }
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn close_span(&mut self) -> io::Result<()> {
+        if self.is_span_open {
+            // Keep the elements nested by closing a link inside the span
+            if !self.is_span_in_link {
+                self.close_link()?;
+            }
+            self.writer.write_all(b"</span>")?;
+            self.is_span_open = false;
+        }
+        Ok(())
+    }
+
+    fn close_link(&mut self) -> io::Result<()> {
+        if self.is_link_open {
+            if self.is_span_in_link {
+                self.close_span()?;
+            }
+            self.writer.write_all(b"</a>")?;
+            self.is_link_open = false;
+        }
+        Ok(())
+    }
+}
+
+impl<W: io::Write + ?Sized> io::Write for HtmlWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut start = 0;
+        for (i, &byte) in buf.iter().enumerate() {
+            if let Some(sequence) = self.escape.as_mut() {
+                sequence.push(byte);
+                self.continue_escape()?;
+                start = i + 1;
+            } else if byte == 0x1b {
+                self.write_text(&buf[start..i])?;
+                self.escape = Some(vec![byte]);
+                start = i + 1;
+            }
+        }
+        if self.escape.is_none() {
+            self.write_text(&buf[start..])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W: io::Write + ?Sized> WriteColor for HtmlWriter<'_, W> {
+    fn supports_color(&self) -> bool {
+        true
+    }
+
+    fn supports_hyperlinks(&self) -> bool {
+        true
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        self.close_span()?;
+        let name = match spec.fg() {
+            Some(Color::Ansi256(i)) => Styles::NAMES.get(*i as usize),
+            _ => None,
+        };
+        if let Some(name) = name {
+            write!(self.writer, "<span class=\"{}\">", class_name(name))?;
+            self.is_span_open = true;
+            self.is_span_in_link = self.is_link_open;
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        self.close_span()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    fn write_html(write: impl FnOnce(&mut HtmlWriter<'_, Vec<u8>>) -> io::Result<()>) -> String {
+        let mut output = Vec::new();
+        let mut writer = HtmlWriter::new(&mut output);
+        write(&mut writer).unwrap();
+        writer.finish().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        let html = write_html(|writer| write!(writer, "if a < b && c > \"d\" {{ 'e' }}"));
+
+        assert_eq!(
+            html,
+            "if a &lt; b &amp;&amp; c &gt; &quot;d&quot; { &#39;e&#39; }",
+        );
+    }
+
+    #[test]
+    fn styles_are_classes() {
+        let styles = class_styles();
+        let html = write_html(|writer| {
+            writer.set_color(&styles.header_error)?;
+            write!(writer, "error")?;
+            writer.set_color(&styles.header_message)?;
+            write!(writer, ": oops")?;
+            writer.reset()?;
+            write!(writer, " plain")
+        });
+
+        assert_eq!(
+            html,
+            "<span class=\"header-error\">error</span>\
+             <span class=\"header-message\">: oops</span> plain",
+        );
+    }
+
+    #[test]
+    fn hyperlinks_are_anchors() {
+        let styles = class_styles();
+        let html = write_html(|writer| {
+            writer.set_color(&styles.header_error)?;
+            write!(writer, "error\x1b]8;;https://example.com/?a=1&b=2\x1b\\")?;
+            write!(writer, "[E0001]\x1b]8;;\x1b\\")?;
+            writer.reset()
+        });
+
+        assert_eq!(
+            html,
+            "<span class=\"header-error\">error\
+             <a href=\"https://example.com/?a=1&amp;b=2\">[E0001]</a></span>",
+        );
+    }
+
+    #[test]
+    fn hyperlinks_split_across_writes() {
+        let html = write_html(|writer| {
+            for chunk in &["\x1b", "]8;", ";file:///a", "\x1b", "\\a\x1b]8", ";;\x1b\\"] {
+                writer.write_all(chunk.as_bytes())?;
+            }
+            Ok(())
+        });
+
+        assert_eq!(html, "<a href=\"file:///a\">a</a>");
+    }
+
+    #[test]
+    fn other_escape_sequences_are_text() {
+        let html = write_html(|writer| write!(writer, "a\x1b[1mb"));
+
+        assert_eq!(html, "a\x1b[1mb");
+    }
+
+    #[test]
+    fn stylesheet_uses_class_names() {
+        let stylesheet = stylesheet(&Styles::with_blue(Color::Blue));
+
+        assert!(
+            stylesheet.contains(".codespan .header-error { color: #ff5555; font-weight: bold; }\n")
+        );
+        assert!(stylesheet.contains(".codespan .line-number { color: #0000aa; }\n"));
+    }
+}
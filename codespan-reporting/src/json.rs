@@ -0,0 +1,507 @@
+//! JSON output that is compatible with rustc's `--error-format=json`.
+//!
+//! Each diagnostic is written as a single JSON object on its own line, using
+//! the same schema as rustc. This allows editors and CI tools that already
+//! understand rustc's diagnostics to consume diagnostics from other languages.
+//!
+//! This module requires the `json` feature to be enabled.
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Diagnostic, Label};
+//! use codespan_reporting::files::SimpleFiles;
+//! use codespan_reporting::json::{self, Config};
+//!
+//! let mut files = SimpleFiles::new();
+//! let file_id = files.add("test", "let x = 1;");
+//!
+//! let diagnostic = Diagnostic::warning()
+//!     .with_message("unused variable: `x`")
+//!     .with_labels(vec![Label::primary(file_id, 4..5)]);
+//!
+//! let mut output = Vec::new();
+//! json::emit(&mut output, &Config::default(), &files, &diagnostic).unwrap();
+//!
+//! let output = String::from_utf8(output).unwrap();
+//! assert!(output.starts_with(r#"{"message":"unused variable: `x`","code":null,"level":"warning""#));
+//! assert!(output.ends_with("}\n"));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::ops::Range;
+use termcolor::{Ansi, NoColor};
+
+use crate::diagnostic::{self, Applicability, LabelStyle, Severity, SubDiagnostic, Suggestion};
+use crate::explain::Registry;
+use crate::files::Files;
+use crate::term;
+
+/// Configures how diagnostics are converted to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// The config used to produce the `rendered` field of diagnostics.
+    pub term: term::Config,
+    /// Include ANSI color escape sequences in the `rendered` field.
+    /// Defaults to: `false`.
+    pub ansi: bool,
+    /// Explanations of diagnostic codes, used to fill in the `explanation`
+    /// field of the codes.
+    pub explanations: Registry,
+}
+
+/// A diagnostic, in the format produced by rustc's `--error-format=json`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// The primary message of the diagnostic.
+    pub message: String,
+    /// The code of the diagnostic.
+    pub code: Option<DiagnosticCode>,
+    /// The severity of the diagnostic: `"error: internal compiler error"`,
+    /// `"error"`, `"warning"`, `"note"`, or `"help"`.
+    pub level: String,
+    /// The locations in the source code that the diagnostic refers to.
+    pub spans: Vec<DiagnosticSpan>,
+    /// Notes, child diagnostics, and suggestions attached to the diagnostic.
+    pub children: Vec<Diagnostic>,
+    /// The diagnostic as it would be displayed in the terminal. This is only
+    /// set on top-level diagnostics.
+    pub rendered: Option<String>,
+}
+
+/// The code of a diagnostic.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticCode {
+    /// The code itself, for example `"E0001"`.
+    pub code: String,
+    /// The extended explanation of the code, if one is available.
+    pub explanation: Option<String>,
+}
+
+/// A location in the source code.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticSpan {
+    /// The origin of the file that the span is in.
+    pub file_name: String,
+    /// The byte offset where the span starts (0-based, inclusive).
+    pub byte_start: usize,
+    /// The byte offset where the span ends (0-based, exclusive).
+    pub byte_end: usize,
+    /// The line where the span starts (1-based, inclusive).
+    pub line_start: usize,
+    /// The line where the span ends (1-based, inclusive).
+    pub line_end: usize,
+    /// The column where the span starts (1-based, inclusive).
+    pub column_start: usize,
+    /// The column where the span ends (1-based, exclusive).
+    pub column_end: usize,
+    /// Whether this is a primary span.
+    pub is_primary: bool,
+    /// The source lines covered by the span.
+    pub text: Vec<DiagnosticSpanLine>,
+    /// The message attached to the span.
+    pub label: Option<String>,
+    /// The text that a suggestion would replace the span with.
+    pub suggested_replacement: Option<String>,
+    /// The confidence that the suggestion is correct.
+    pub suggestion_applicability: Option<Applicability>,
+    /// The macro expansion that the span was produced by. Diagnostics have no
+    /// notion of macros, so this is always `None` when converting them.
+    pub expansion: Option<Box<DiagnosticSpanMacroExpansion>>,
+}
+
+/// The macro expansion that a span was produced by.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticSpanMacroExpansion {
+    /// The span of the macro invocation.
+    pub span: DiagnosticSpan,
+    /// The name of the macro, for example `"println!"`.
+    pub macro_decl_name: String,
+    /// The span of the definition of the macro.
+    pub def_site_span: DiagnosticSpan,
+}
+
+/// A line of source code covered by a span.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticSpanLine {
+    /// The source text of the line, without the trailing line ending.
+    pub text: String,
+    /// The column where the span starts on this line (1-based, inclusive).
+    pub highlight_start: usize,
+    /// The column where the span ends on this line (1-based, exclusive).
+    pub highlight_end: usize,
+}
+
+impl Diagnostic {
+    /// Convert a diagnostic to the JSON format, using the given config and
+    /// files.
+    pub fn from_diagnostic<'files, F: Files<'files>>(
+        config: &Config,
+        files: &'files F,
+        diagnostic: &diagnostic::Diagnostic<F::FileId>,
+    ) -> io::Result<Diagnostic> {
+        let mut rendered = Vec::new();
+        if config.ansi {
+            term::emit(
+                &mut Ansi::new(&mut rendered),
+                &config.term,
+                files,
+                diagnostic,
+            )?;
+        } else {
+            term::emit(
+                &mut NoColor::new(&mut rendered),
+                &config.term,
+                files,
+                diagnostic,
+            )?;
+        }
+
+        let code = diagnostic.code.as_ref().map(|code| DiagnosticCode {
+            code: code.clone(),
+            explanation: config.explanations.get(code).map(str::to_owned),
+        });
+
+        let notes = diagnostic.notes.iter().map(|note| Diagnostic {
+            message: note.clone(),
+            code: None,
+            level: level(Severity::Note).to_owned(),
+            spans: Vec::new(),
+            children: Vec::new(),
+            rendered: None,
+        });
+        let children = diagnostic
+            .children
+            .iter()
+            .map(|child| from_child(files, child));
+        let suggestions = diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| from_suggestion(files, suggestion));
+
+        Ok(Diagnostic {
+            message: diagnostic.message.clone(),
+            code,
+            level: level(diagnostic.severity).to_owned(),
+            spans: diagnostic
+                .labels
+                .iter()
+                .map(|label| from_label(files, label))
+                .collect(),
+            children: notes.chain(children).chain(suggestions).collect(),
+            rendered: Some(String::from_utf8_lossy(&rendered).into_owned()),
+        })
+    }
+}
+
+/// Emit a diagnostic as a single line of JSON, using the given writer, config,
+/// and files.
+pub fn emit<'files, F: Files<'files>>(
+    writer: &mut (impl io::Write + ?Sized),
+    config: &Config,
+    files: &'files F,
+    diagnostic: &diagnostic::Diagnostic<F::FileId>,
+) -> io::Result<()> {
+    let diagnostic = Diagnostic::from_diagnostic(config, files, diagnostic)?;
+    serde_json::to_writer(&mut *writer, &diagnostic)?;
+    writeln!(writer)
+}
+
+/// The name that rustc uses for the level of a severity.
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug => "error: internal compiler error",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    }
+}
+
+fn from_child<'files, F: Files<'files>>(
+    files: &'files F,
+    child: &SubDiagnostic<F::FileId>,
+) -> Diagnostic {
+    Diagnostic {
+        message: child.message.clone(),
+        code: None,
+        level: level(child.severity).to_owned(),
+        spans: child
+            .labels
+            .iter()
+            .map(|label| from_label(files, label))
+            .collect(),
+        children: Vec::new(),
+        rendered: None,
+    }
+}
+
+fn from_suggestion<'files, F: Files<'files>>(
+    files: &'files F,
+    suggestion: &Suggestion<F::FileId>,
+) -> Diagnostic {
+    let span = DiagnosticSpan {
+        suggested_replacement: Some(suggestion.replacement.clone()),
+        suggestion_applicability: Some(suggestion.applicability),
+        ..span(files, suggestion.file_id, &suggestion.range, true, None)
+    };
+
+    Diagnostic {
+        message: suggestion.message.clone(),
+        code: None,
+        level: level(Severity::Help).to_owned(),
+        spans: vec![span],
+        children: Vec::new(),
+        rendered: None,
+    }
+}
+
+fn from_label<'files, F: Files<'files>>(
+    files: &'files F,
+    label: &diagnostic::Label<F::FileId>,
+) -> DiagnosticSpan {
+    let message = match label.message.as_str() {
+        "" => None,
+        message => Some(message.to_owned()),
+    };
+
+    let is_primary = label.style == LabelStyle::Primary;
+    match label.range.as_ref() {
+        Some(range) => span(files, label.file_id, range, is_primary, message),
+        // rustc's format has no way of referring to a whole file, so we
+        // point to the start of the file without covering any source code
+        None => DiagnosticSpan {
+            file_name: files.origin(label.file_id).expect("origin").to_string(),
+            byte_start: 0,
+            byte_end: 0,
+            line_start: 1,
+            line_end: 1,
+            column_start: 1,
+            column_end: 1,
+            is_primary,
+            text: Vec::new(),
+            label: message,
+            suggested_replacement: None,
+            suggestion_applicability: None,
+            expansion: None,
+        },
+    }
+}
+
+fn span<'files, F: Files<'files>>(
+    files: &'files F,
+    file_id: F::FileId,
+    range: &Range<usize>,
+    is_primary: bool,
+    label: Option<String>,
+) -> DiagnosticSpan {
+    let origin = files.origin(file_id).expect("origin");
+    let start_line_index = files
+        .line_index(file_id, range.start)
+        .expect("start_line_index");
+    let end_line_index = files
+        .line_index(file_id, range.end)
+        .expect("end_line_index");
+    let start_line = files.line(file_id, start_line_index).expect("start_line");
+    let end_line = files.line(file_id, end_line_index).expect("end_line");
+
+    let text = (start_line_index..=end_line_index)
+        .map(|line_index| {
+            let line = files.line(file_id, line_index).expect("line");
+            let source = line
+                .source
+                .as_ref()
+                .trim_end_matches(|ch| ch == '\n' || ch == '\r');
+
+            DiagnosticSpanLine {
+                text: source.to_owned(),
+                highlight_start: match line_index {
+                    _ if line_index == start_line_index => line.column_number(range.start),
+                    _ => 1,
+                },
+                highlight_end: match line_index {
+                    _ if line_index == end_line_index => line.column_number(range.end),
+                    _ => source.chars().count() + 1,
+                },
+            }
+        })
+        .collect();
+
+    DiagnosticSpan {
+        file_name: origin.to_string(),
+        byte_start: range.start,
+        byte_end: range.end,
+        line_start: start_line.number,
+        line_end: end_line.number,
+        column_start: start_line.column_number(range.start),
+        column_end: end_line.column_number(range.end),
+        is_primary,
+        text,
+        label,
+        suggested_replacement: None,
+        suggestion_applicability: None,
+        expansion: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::diagnostic::Label;
+    use crate::files::SimpleFiles;
+
+    fn files() -> (SimpleFiles<&'static str, &'static str>, usize) {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test", "let x = foo(a,\n    b);\nlet y = x;\n");
+        (files, file_id)
+    }
+
+    #[test]
+    fn spans() {
+        let (files, file_id) = files();
+        let diagnostic = diagnostic::Diagnostic::error()
+            .with_message("mismatched types")
+            .with_labels(vec![
+                Label::primary(file_id, 8..21).with_message("expected `Int`"),
+                Label::secondary(file_id, 4..5),
+            ]);
+
+        let json = Diagnostic::from_diagnostic(&Config::default(), &files, &diagnostic).unwrap();
+
+        assert_eq!(json.level, "error");
+        assert_eq!(
+            json.spans[0],
+            DiagnosticSpan {
+                file_name: "test".to_owned(),
+                byte_start: 8,
+                byte_end: 21,
+                line_start: 1,
+                line_end: 2,
+                column_start: 9,
+                column_end: 7,
+                is_primary: true,
+                text: vec![
+                    DiagnosticSpanLine {
+                        text: "let x = foo(a,".to_owned(),
+                        highlight_start: 9,
+                        highlight_end: 15,
+                    },
+                    DiagnosticSpanLine {
+                        text: "    b);".to_owned(),
+                        highlight_start: 1,
+                        highlight_end: 7,
+                    },
+                ],
+                label: Some("expected `Int`".to_owned()),
+                suggested_replacement: None,
+                suggestion_applicability: None,
+                expansion: None,
+            },
+        );
+        assert!(!json.spans[1].is_primary);
+        assert_eq!(json.spans[1].label, None);
+        assert_eq!(json.spans[1].column_start, 5);
+        assert_eq!(json.spans[1].column_end, 6);
+    }
+
+    #[test]
+    fn spans_have_expansions() {
+        let (files, file_id) = files();
+        let diagnostic = diagnostic::Diagnostic::error()
+            .with_message("mismatched types")
+            .with_labels(vec![Label::primary(file_id, 4..5)]);
+
+        let json = Diagnostic::from_diagnostic(&Config::default(), &files, &diagnostic).unwrap();
+        let span = serde_json::to_string(&json.spans[0]).unwrap();
+
+        assert!(span.ends_with(r#""suggestion_applicability":null,"expansion":null}"#));
+    }
+
+    #[test]
+    fn file_labels() {
+        let (files, file_id) = files();
+        let diagnostic = diagnostic::Diagnostic::error()
+            .with_message("missing module header")
+            .with_labels(vec![Label::primary_file(file_id)]);
+
+        let json = Diagnostic::from_diagnostic(&Config::default(), &files, &diagnostic).unwrap();
+
+        assert_eq!(json.spans[0].file_name, "test");
+        assert_eq!((json.spans[0].byte_start, json.spans[0].byte_end), (0, 0));
+        assert_eq!(
+            (json.spans[0].line_start, json.spans[0].column_start),
+            (1, 1)
+        );
+        assert!(json.spans[0].text.is_empty());
+    }
+
+    #[test]
+    fn children() {
+        let (files, file_id) = files();
+        let diagnostic = diagnostic::Diagnostic::bug()
+            .with_message("unexpected panic")
+            .with_code("E0001")
+            .with_notes(vec!["the compiler crashed".to_owned()])
+            .with_children(vec![SubDiagnostic::help()
+                .with_message("the previous definition is here")
+                .with_labels(vec![Label::primary(file_id, 27..28)])])
+            .with_suggestions(vec![Suggestion::new(file_id, 27..28, "z")
+                .with_message("rename the binding")
+                .with_applicability(Applicability::MaybeIncorrect)]);
+
+        let config = Config {
+            explanations: Registry::new().with_explanation("E0001", "# Unexpected panic"),
+            ..Config::default()
+        };
+        let json = Diagnostic::from_diagnostic(&config, &files, &diagnostic).unwrap();
+
+        assert_eq!(json.level, "error: internal compiler error");
+        assert_eq!(
+            json.code,
+            Some(DiagnosticCode {
+                code: "E0001".to_owned(),
+                explanation: Some("# Unexpected panic".to_owned()),
+            }),
+        );
+
+        let levels = json
+            .children
+            .iter()
+            .map(|child| child.level.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(levels, ["note", "help", "help"]);
+        assert!(json.children.iter().all(|child| child.rendered.is_none()));
+
+        let suggestion = &json.children[2].spans[0];
+        assert_eq!(suggestion.line_start, 3);
+        assert_eq!(suggestion.column_start, 5);
+        assert_eq!(suggestion.suggested_replacement, Some("z".to_owned()));
+        assert_eq!(
+            suggestion.suggestion_applicability,
+            Some(Applicability::MaybeIncorrect),
+        );
+    }
+
+    #[test]
+    fn emit_one_object_per_line() {
+        let (files, _) = files();
+        let diagnostic = diagnostic::Diagnostic::warning().with_message("unused\nvariable");
+
+        let mut output = Vec::new();
+        emit(&mut output, &Config::default(), &files, &diagnostic).unwrap();
+        emit(&mut output, &Config::default(), &files, &diagnostic).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            concat!(
+                r#"{"message":"unused\nvariable","code":null,"level":"warning","spans":[],"#,
+                r#""children":[],"rendered":"warning: unused\nvariable\n\n"}"#,
+            ),
+        );
+    }
+}
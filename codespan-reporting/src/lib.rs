@@ -1,5 +1,19 @@
 //! Diagnostic reporting support for the codespan crate.
 
 pub mod diagnostic;
+mod escape;
+pub mod explain;
 pub mod files;
+pub mod fix;
+pub mod github;
+#[cfg(feature = "gitlab")]
+pub mod gitlab;
+pub mod html;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod lint;
+pub mod markdown;
+#[cfg(feature = "sarif")]
+pub mod sarif;
 pub mod term;
+pub mod xml;
@@ -0,0 +1,326 @@
+//! Lint levels, for controlling the severity of diagnostics by their code.
+//!
+//! This allows users to silence or escalate individual lints, or groups of
+//! lints, in a similar way to rustc's `-A`, `-W`, `-D`, and `-F` flags.
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Diagnostic, Severity};
+//! use codespan_reporting::lint::{Level, LintLevels};
+//!
+//! let mut lint_levels = LintLevels::new();
+//! lint_levels.add_group("unused", vec!["W0001", "W0002"]);
+//! lint_levels.set("unused", Level::Deny).unwrap();
+//! lint_levels.set("W0002", Level::Allow).unwrap();
+//!
+//! let diagnostic = Diagnostic::<()>::warning().with_code("W0001");
+//! let diagnostic = lint_levels.apply(diagnostic).unwrap();
+//! assert_eq!(diagnostic.severity, Severity::Error);
+//!
+//! let diagnostic = Diagnostic::<()>::warning().with_code("W0002");
+//! assert!(lint_levels.apply(diagnostic).is_none());
+//! ```
+
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::diagnostic::{Diagnostic, Severity};
+
+/// The level of a lint.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub enum Level {
+    /// Diagnostics for the lint are dropped.
+    Allow,
+    /// Diagnostics for the lint are emitted as warnings.
+    Warn,
+    /// Diagnostics for the lint are emitted as errors.
+    Deny,
+    /// Diagnostics for the lint are emitted as errors, and the level can no
+    /// longer be changed.
+    Forbid,
+}
+
+impl Level {
+    /// Allowed values of the level when parsed from a string.
+    ///
+    /// This is useful for generating documentation via `clap` or `structopt`'s
+    /// `possible_values` configuration.
+    pub const VARIANTS: &'static [&'static str] = &["allow", "warn", "deny", "forbid"];
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Allow => "allow",
+            Level::Warn => "warn",
+            Level::Deny => "deny",
+            Level::Forbid => "forbid",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Level {
+    type Err = &'static str;
+
+    fn from_str(src: &str) -> Result<Level, &'static str> {
+        match src {
+            _ if src.eq_ignore_ascii_case("allow") => Ok(Level::Allow),
+            _ if src.eq_ignore_ascii_case("warn") => Ok(Level::Warn),
+            _ if src.eq_ignore_ascii_case("deny") => Ok(Level::Deny),
+            _ if src.eq_ignore_ascii_case("forbid") => Ok(Level::Forbid),
+            _ => Err("valid values: allow, warn, deny, forbid"),
+        }
+    }
+}
+
+/// An error that occurs when attempting to change the level of a lint that
+/// was previously forbidden.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForbiddenLintError {
+    /// The name of the lint or group whose level was being set.
+    pub name: String,
+    /// The code of the forbidden lint.
+    pub code: String,
+    /// The level that was being set.
+    pub level: Level,
+}
+
+impl fmt::Display for ForbiddenLintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}({}) incompatible with previous forbid({})",
+            self.level, self.name, self.code,
+        )
+    }
+}
+
+impl std::error::Error for ForbiddenLintError {}
+
+/// A registry of lint levels, keyed by diagnostic code.
+///
+/// Levels can be set for individual codes, or for named groups of codes.
+/// Later settings take precedence over earlier ones, except for lints that
+/// have been forbidden, which can not be changed to any other level.
+///
+/// Levels only take effect for codes that have been registered as lints, with
+/// `LintLevels::register` or `LintLevels::add_group`, so that hard errors can
+/// not be allowed or downgraded to warnings.
+#[derive(Clone, Debug, Default)]
+pub struct LintLevels {
+    lints: HashSet<String>,
+    levels: HashMap<String, Level>,
+    groups: HashMap<String, Vec<String>>,
+    warnings_as_errors: bool,
+}
+
+impl LintLevels {
+    /// Create a new registry, with no lint levels set.
+    pub fn new() -> LintLevels {
+        LintLevels::default()
+    }
+
+    /// Register a diagnostic code as a lint, so that its level can be
+    /// adjusted.
+    pub fn register(&mut self, code: impl Into<String>) {
+        self.lints.insert(code.into());
+    }
+
+    /// Returns `true` if the given diagnostic code was registered as a lint.
+    pub fn is_lint(&self, code: &str) -> bool {
+        self.lints.contains(code)
+    }
+
+    /// Register a named group of lint codes, so that their levels can be set
+    /// together.
+    pub fn add_group<Code: Into<String>>(
+        &mut self,
+        name: impl Into<String>,
+        codes: impl IntoIterator<Item = Code>,
+    ) {
+        let codes: Vec<String> = codes.into_iter().map(Into::into).collect();
+        self.lints.extend(codes.iter().cloned());
+        self.groups.insert(name.into(), codes);
+    }
+
+    /// Set the level of a lint code, or of all of the lint codes in a group.
+    ///
+    /// If any of the affected lints were previously forbidden, no levels are
+    /// changed and an error is returned.
+    pub fn set(&mut self, name: &str, level: Level) -> Result<(), ForbiddenLintError> {
+        let codes = match self.groups.get(name) {
+            Some(codes) => codes.clone(),
+            None => vec![name.to_owned()],
+        };
+
+        for code in &codes {
+            if level != Level::Forbid && self.level(code) == Some(Level::Forbid) {
+                return Err(ForbiddenLintError {
+                    name: name.to_owned(),
+                    code: code.clone(),
+                    level,
+                });
+            }
+        }
+
+        for code in codes {
+            self.levels.insert(code, level);
+        }
+
+        Ok(())
+    }
+
+    /// Treat all warnings as errors, unless they are explicitly allowed.
+    pub fn set_warnings_as_errors(&mut self, warnings_as_errors: bool) {
+        self.warnings_as_errors = warnings_as_errors;
+    }
+
+    /// The level that has been set for the given lint code.
+    pub fn level(&self, code: &str) -> Option<Level> {
+        self.levels.get(code).cloned()
+    }
+
+    /// Apply the lint levels to a diagnostic, returning `None` if it should be
+    /// dropped.
+    ///
+    /// Only diagnostics with a severity of `Severity::Warning` or
+    /// `Severity::Error` are affected by lint levels. Bugs, notes and help
+    /// messages are returned unchanged.
+    ///
+    /// Diagnostics whose code was not registered as a lint are never dropped
+    /// or downgraded, although warnings are still treated as errors if
+    /// requested.
+    pub fn apply<FileId>(&self, mut diagnostic: Diagnostic<FileId>) -> Option<Diagnostic<FileId>> {
+        match diagnostic.severity {
+            Severity::Warning | Severity::Error => {}
+            Severity::Bug | Severity::Note | Severity::Help => return Some(diagnostic),
+        }
+
+        let level = match &diagnostic.code {
+            Some(code) if self.is_lint(code) => self.level(code),
+            Some(_) | None => None,
+        };
+
+        match level {
+            Some(Level::Allow) => return None,
+            Some(Level::Warn) => diagnostic.severity = Severity::Warning,
+            Some(Level::Deny) | Some(Level::Forbid) => diagnostic.severity = Severity::Error,
+            None => {}
+        }
+
+        if self.warnings_as_errors && diagnostic.severity == Severity::Warning {
+            diagnostic.severity = Severity::Error;
+        }
+
+        Some(diagnostic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn severity(lint_levels: &LintLevels, diagnostic: Diagnostic<()>) -> Option<Severity> {
+        lint_levels
+            .apply(diagnostic)
+            .map(|diagnostic| diagnostic.severity)
+    }
+
+    #[test]
+    fn levels() {
+        let mut lint_levels = LintLevels::new();
+        for code in &["L1", "L2", "L3", "L4"] {
+            lint_levels.register(*code);
+        }
+        lint_levels.set("L1", Level::Allow).unwrap();
+        lint_levels.set("L2", Level::Warn).unwrap();
+        lint_levels.set("L3", Level::Deny).unwrap();
+
+        let lint = |code| Diagnostic::warning().with_code(code);
+        assert_eq!(severity(&lint_levels, lint("L1")), None);
+        assert_eq!(severity(&lint_levels, lint("L2")), Some(Severity::Warning));
+        assert_eq!(severity(&lint_levels, lint("L3")), Some(Severity::Error));
+        assert_eq!(severity(&lint_levels, lint("L4")), Some(Severity::Warning));
+
+        let deny_by_default = Diagnostic::error().with_code("L2");
+        assert_eq!(
+            severity(&lint_levels, deny_by_default),
+            Some(Severity::Warning)
+        );
+
+        let note = Diagnostic::note().with_code("L1");
+        assert_eq!(severity(&lint_levels, note), Some(Severity::Note));
+    }
+
+    #[test]
+    fn unregistered_codes() {
+        let mut lint_levels = LintLevels::new();
+        lint_levels.register("L1");
+        lint_levels.set("E0308", Level::Allow).unwrap();
+        lint_levels.set("E0309", Level::Warn).unwrap();
+        lint_levels.set("W0001", Level::Allow).unwrap();
+
+        let error = |code| Diagnostic::error().with_code(code);
+        assert_eq!(
+            severity(&lint_levels, error("E0308")),
+            Some(Severity::Error)
+        );
+        assert_eq!(
+            severity(&lint_levels, error("E0309")),
+            Some(Severity::Error)
+        );
+
+        let warning = Diagnostic::warning().with_code("W0001");
+        assert_eq!(severity(&lint_levels, warning), Some(Severity::Warning));
+    }
+
+    #[test]
+    fn groups() {
+        let mut lint_levels = LintLevels::new();
+        lint_levels.add_group("unused", vec!["L1", "L2"]);
+        lint_levels.set("unused", Level::Deny).unwrap();
+        lint_levels.set("L2", Level::Allow).unwrap();
+
+        assert_eq!(lint_levels.level("L1"), Some(Level::Deny));
+        assert_eq!(lint_levels.level("L2"), Some(Level::Allow));
+        assert_eq!(lint_levels.level("L3"), None);
+    }
+
+    #[test]
+    fn forbid() {
+        let mut lint_levels = LintLevels::new();
+        lint_levels.add_group("unused", vec!["L1", "L2"]);
+        lint_levels.set("L1", Level::Forbid).unwrap();
+        lint_levels.set("L1", Level::Forbid).unwrap();
+
+        let error = lint_levels.set("unused", Level::Allow).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "allow(unused) incompatible with previous forbid(L1)"
+        );
+        assert_eq!(lint_levels.level("L1"), Some(Level::Forbid));
+        assert_eq!(lint_levels.level("L2"), None);
+    }
+
+    #[test]
+    fn warnings_as_errors() {
+        let mut lint_levels = LintLevels::new();
+        lint_levels.register("L1");
+        lint_levels.set("L1", Level::Allow).unwrap();
+        lint_levels.set_warnings_as_errors(true);
+
+        let warning = Diagnostic::warning();
+        assert_eq!(severity(&lint_levels, warning), Some(Severity::Error));
+        let allowed = Diagnostic::warning().with_code("L1");
+        assert_eq!(severity(&lint_levels, allowed), None);
+    }
+}
@@ -0,0 +1,347 @@
+//! GitHub-flavoured Markdown back-end for emitting diagnostics, for example in
+//! pull request review comments.
+//!
+//! A diagnostic is rendered as a heading with its severity, code, and message,
+//! followed by its source snippets in a fenced code block, its notes in a
+//! list, and its child diagnostics and suggestions under smaller headings:
+//!
+//! ````markdown
+//! ### error[E0001]: unexpected type in `+` application
+//!
+//! ```text
+//!   ┌─ test:2:9
+//!   │
+//! 2 │ (+ test "")
+//!   │         ^^ expected `Int` but found `String`
+//!   │
+//! ```
+//!
+//! - expected type `Int`\
+//!   found type `String`
+//! ````
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Diagnostic, Label};
+//! use codespan_reporting::files::SimpleFiles;
+//! use codespan_reporting::markdown::{self, Config};
+//!
+//! let mut files = SimpleFiles::new();
+//! let file_id = files.add("src/main.fun", "let x = 1;");
+//!
+//! let diagnostic = Diagnostic::warning()
+//!     .with_message("unused variable: `x`")
+//!     .with_labels(vec![Label::primary(file_id, 4..5)]);
+//!
+//! let config = Config {
+//!     permalink_template: Some("https://github.com/owner/repo/blob/main/{path}#L{line}".to_owned()),
+//!     ..Config::default()
+//! };
+//! let mut output = Vec::new();
+//! markdown::emit(&mut output, &config, &files, &diagnostic).unwrap();
+//! ```
+
+use std::io;
+use termcolor::NoColor;
+
+use crate::diagnostic::{Diagnostic, Label, Suggestion};
+use crate::files::Files;
+use crate::term::{self, severity_name};
+
+/// Configures how diagnostics are rendered as Markdown.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The config used to lay out the source snippets.
+    /// Defaults to: `term::Config::default()`.
+    pub term: term::Config,
+    /// The level of the headings of diagnostics, from `1` to `5`. Child
+    /// diagnostics and suggestions use the following level.
+    /// Defaults to: `3`.
+    pub heading_level: usize,
+    /// A template for links to the locations of labels, where `{path}` is
+    /// replaced by the percent-encoded origin of the file, and `{line}` by the
+    /// line number.
+    /// For example `"https://github.com/owner/repo/blob/main/{path}#L{line}"`.
+    /// Defaults to: `None`.
+    pub permalink_template: Option<String>,
+}
+
+impl Config {
+    /// Create a new config that lays out source snippets with the given term
+    /// config.
+    pub fn new(term: term::Config) -> Config {
+        Config {
+            term,
+            heading_level: 3,
+            permalink_template: None,
+        }
+    }
+
+    /// The permalink to a line of a file, if a template is configured.
+    fn permalink(&self, origin: &str, line_number: usize) -> Option<String> {
+        let template = self.permalink_template.as_ref()?;
+        Some(
+            template
+                .replace("{path}", &term::percent_encode(&origin.replace('\\', "/")))
+                .replace("{line}", &line_number.to_string()),
+        )
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::new(term::Config::default())
+    }
+}
+
+/// Emit a diagnostic as GitHub-flavoured Markdown, using the given writer,
+/// config, and files.
+pub fn emit<'files, F: Files<'files>>(
+    writer: &mut (impl io::Write + ?Sized),
+    config: &Config,
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+) -> io::Result<()> {
+    // ```markdown
+    // ### error[E0001]: unexpected type in `+` application
+    // ```
+    let mut title = severity_name(diagnostic.severity).to_owned();
+    if let Some(code) = &diagnostic.code {
+        title.push_str(&format!("[{}]", code));
+    }
+    emit_heading(writer, config.heading_level, &title, &diagnostic.message)?;
+    emit_labels(writer, config, files, diagnostic)?;
+
+    // ```markdown
+    // - expected type `Int`\
+    //   found type `String`
+    // ```
+    if !diagnostic.notes.is_empty() {
+        writeln!(writer)?;
+        for note in &diagnostic.notes {
+            let lines = note.lines().map(|line| escape(line.trim()));
+            writeln!(writer, "- {}", lines.collect::<Vec<_>>().join("\\\n  "))?;
+        }
+    }
+
+    for child in &diagnostic.children {
+        writeln!(writer)?;
+        let severity = severity_name(child.severity);
+        emit_heading(writer, config.heading_level + 1, severity, &child.message)?;
+        let child = Diagnostic::new(child.severity).with_labels(child.labels.clone());
+        emit_labels(writer, config, files, &child)?;
+    }
+
+    for suggestion in &diagnostic.suggestions {
+        writeln!(writer)?;
+        emit_heading(
+            writer,
+            config.heading_level + 1,
+            "help",
+            &suggestion.message,
+        )?;
+        emit_suggestion(writer, files, suggestion)?;
+    }
+
+    Ok(())
+}
+
+/// Write a heading, with the lines of the message after the first one in a
+/// paragraph below it.
+fn emit_heading(
+    writer: &mut (impl io::Write + ?Sized),
+    level: usize,
+    title: &str,
+    message: &str,
+) -> io::Result<()> {
+    let mut lines = message.lines();
+    write!(writer, "{} {}", "#".repeat(level.min(6)), title)?;
+    match lines.next() {
+        Some(line) => writeln!(writer, ": {}", escape(line))?,
+        None => writeln!(writer)?,
+    }
+    let rest = lines.map(|line| escape(line.trim())).collect::<Vec<_>>();
+    if !rest.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "{}", rest.join("\\\n"))?;
+    }
+    Ok(())
+}
+
+/// Write the permalinks to the labels and their source snippets.
+fn emit_labels<'files, F: Files<'files>>(
+    writer: &mut (impl io::Write + ?Sized),
+    config: &Config,
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+) -> io::Result<()> {
+    if diagnostic.labels.is_empty() {
+        return Ok(());
+    }
+
+    // ```markdown
+    // [`test:2:9`](https://github.com/owner/repo/blob/main/test#L2)
+    // ```
+    let links = diagnostic
+        .labels
+        .iter()
+        .filter_map(|label| permalink(config, files, label))
+        .fold(Vec::new(), |mut links, link| {
+            if !links.contains(&link) {
+                links.push(link);
+            }
+            links
+        });
+    if !links.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "{}", links.join(", "))?;
+    }
+
+    let mut snippets = NoColor::new(Vec::new());
+    term::emit_snippets(&mut snippets, &config.term, files, diagnostic)?;
+    let snippets = String::from_utf8_lossy(snippets.get_ref());
+    writeln!(writer)?;
+    emit_code_block(writer, "text", snippets.trim_end_matches('\n'))
+}
+
+/// A link to the location of a label, named after its locus.
+fn permalink<'files, F: Files<'files>>(
+    config: &Config,
+    files: &'files F,
+    label: &Label<F::FileId>,
+) -> Option<String> {
+    let origin = files.origin(label.file_id)?.to_string();
+    let (locus, line_number) = match label.range.as_ref() {
+        Some(range) => {
+            let line_index = files.line_index(label.file_id, range.start)?;
+            let line = files.line(label.file_id, line_index)?;
+            let column_number = config.term.column_number(&line, range.start);
+            let locus = format!("{}:{}:{}", origin, line.number, column_number);
+            (locus, line.number)
+        }
+        None => (origin.clone(), 1),
+    };
+    let url = config.permalink(&origin, line_number)?;
+    let fence = "`".repeat(longest_backtick_run(&locus) + 1);
+    Some(format!(
+        "[{fence}{locus}{fence}]({url})",
+        fence = fence,
+        locus = locus,
+        url = url
+    ))
+}
+
+/// Write the lines changed by a suggestion as a diff.
+///
+/// ````markdown
+/// ```diff
+/// -(+ test "")
+/// +(+ test 1)
+/// ```
+/// ````
+fn emit_suggestion<'files, F: Files<'files>>(
+    writer: &mut (impl io::Write + ?Sized),
+    files: &'files F,
+    suggestion: &Suggestion<F::FileId>,
+) -> io::Result<()> {
+    let file_id = suggestion.file_id;
+    let start_index = files.line_index(file_id, suggestion.range.start);
+    let end_index = files.line_index(file_id, suggestion.range.end);
+    let (start_index, end_index) = (
+        start_index.expect("start_line"),
+        end_index.expect("end_line"),
+    );
+    let start = files.line(file_id, start_index).expect("start_line").start;
+
+    let mut removed = String::new();
+    for line_index in start_index..=end_index {
+        let line = files.line(file_id, line_index).expect("line");
+        removed.push_str(line.source.as_ref());
+    }
+    let prefix = &removed[..suggestion.range.start - start];
+    let suffix = &removed[suggestion.range.end - start..];
+    let inserted = format!("{}{}{}", prefix, suggestion.replacement, suffix);
+
+    let mut diff = String::new();
+    for line in removed.lines() {
+        diff.push_str(&format!("-{}\n", line));
+    }
+    for line in inserted.lines() {
+        diff.push_str(&format!("+{}\n", line));
+    }
+
+    writeln!(writer)?;
+    emit_code_block(writer, "diff", diff.trim_end_matches('\n'))
+}
+
+/// Write a fenced code block, with a fence that is longer than any run of
+/// backticks in the code.
+fn emit_code_block(
+    writer: &mut (impl io::Write + ?Sized),
+    info: &str,
+    code: &str,
+) -> io::Result<()> {
+    let fence = "`".repeat(std::cmp::max(3, longest_backtick_run(code) + 1));
+    writeln!(writer, "{}{}", fence, info)?;
+    writeln!(writer, "{}", code)?;
+    writeln!(writer, "{}", fence)
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|ch| ch != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// Escape the Markdown syntax in a message, leaving its code spans intact.
+fn escape(message: &str) -> String {
+    let mut escaped = String::with_capacity(message.len());
+    for (i, part) in message.split('`').enumerate() {
+        if i > 0 {
+            escaped.push('`');
+        }
+        // Parts at odd positions are between backticks
+        if i % 2 == 1 {
+            escaped.push_str(part);
+            continue;
+        }
+        for ch in part.chars() {
+            match ch {
+                '\\' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '~' | '|' => {
+                    escaped.push('\\');
+                    escaped.push(ch);
+                }
+                _ => escaped.push(ch),
+            }
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_leaves_code_spans() {
+        assert_eq!(
+            escape("cannot apply `_*_` to <T> and *U*"),
+            "cannot apply `_*_` to \\<T\\> and \\*U\\*",
+        );
+    }
+
+    #[test]
+    fn escape_unclosed_code_span() {
+        assert_eq!(escape("a `b_c"), "a `b_c");
+    }
+
+    #[test]
+    fn code_block_fence_is_longer_than_code() {
+        let mut output = Vec::new();
+        emit_code_block(&mut output, "text", "let s = ````;").unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "`````text\nlet s = ````;\n`````\n",
+        );
+    }
+}
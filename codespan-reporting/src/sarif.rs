@@ -0,0 +1,489 @@
+//! Output in the [Static Analysis Results Interchange Format (SARIF)][sarif],
+//! version 2.1.0.
+//!
+//! SARIF logs can be uploaded to code scanning dashboards, such as GitHub's
+//! code scanning alerts.
+//!
+//! This module requires the `sarif` feature to be enabled.
+//!
+//! [sarif]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Diagnostic, Label};
+//! use codespan_reporting::files::SimpleFiles;
+//! use codespan_reporting::sarif::{self, Config};
+//!
+//! let mut files = SimpleFiles::new();
+//! let file_id = files.add("src/main.fun", "let x = 1;");
+//!
+//! let diagnostics = vec![Diagnostic::warning()
+//!     .with_code("W0001")
+//!     .with_message("unused variable: `x`")
+//!     .with_labels(vec![Label::primary(file_id, 4..5)])];
+//!
+//! let mut output = Vec::new();
+//! let config = Config::new("fun");
+//! sarif::emit(&mut output, &config, &files, &diagnostics).unwrap();
+//! ```
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io;
+use std::ops::Range;
+
+use crate::diagnostic::{Diagnostic, Label, LabelStyle, Severity, Suggestion};
+use crate::files::Files;
+
+/// Configures the tool that is described in the SARIF log.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The name of the tool that produced the diagnostics.
+    pub tool_name: String,
+    /// The version of the tool that produced the diagnostics.
+    /// Defaults to: `None`.
+    pub tool_version: Option<String>,
+    /// A URI pointing to more information about the tool.
+    /// Defaults to: `None`.
+    pub tool_information_uri: Option<String>,
+}
+
+impl Config {
+    /// Create a new config for the tool with the given name.
+    pub fn new(tool_name: impl Into<String>) -> Config {
+        Config {
+            tool_name: tool_name.into(),
+            tool_version: None,
+            tool_information_uri: None,
+        }
+    }
+}
+
+/// Emit a batch of diagnostics as a SARIF log, using the given writer, config,
+/// and files.
+///
+/// The log contains a single run, with one result per diagnostic. The output
+/// only depends on the order of the diagnostics, so it is suitable for golden
+/// tests.
+pub fn emit<'a, 'files, F>(
+    writer: &mut (impl io::Write + ?Sized),
+    config: &Config,
+    files: &'files F,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic<F::FileId>>,
+) -> io::Result<()>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    let log = SarifLog::new(config, files, diagnostics);
+    serde_json::to_writer_pretty(&mut *writer, &log)?;
+    writeln!(writer)
+}
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const VERSION: &str = "2.1.0";
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    /// Columns are counted in characters, like `Line::column_number`.
+    column_kind: &'static str,
+    results: Vec<SarifResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    information_uri: Option<&'a str>,
+    rules: Vec<ReportingDescriptor<'a>>,
+}
+
+#[derive(Serialize)]
+struct ReportingDescriptor<'a> {
+    id: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    level: &'static str,
+    message: Message,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix<'a>>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: usize,
+    byte_length: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<Message>,
+    artifact_changes: Vec<ArtifactChange<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange<'a> {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement<'a> {
+    deleted_region: Region,
+    inserted_content: ArtifactContent<'a>,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent<'a> {
+    text: &'a str,
+}
+
+impl<'a> SarifLog<'a> {
+    fn new<'diagnostics: 'a, 'files, F>(
+        config: &'a Config,
+        files: &'files F,
+        diagnostics: impl IntoIterator<Item = &'diagnostics Diagnostic<F::FileId>>,
+    ) -> SarifLog<'a>
+    where
+        F: Files<'files>,
+        F::FileId: 'diagnostics,
+    {
+        let diagnostics = diagnostics.into_iter().collect::<Vec<_>>();
+
+        // Rules are sorted by their code, so that the output is deterministic
+        let rules = diagnostics
+            .iter()
+            .filter_map(|diagnostic| diagnostic.code.as_ref().map(String::as_str))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let results = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let rule_id = diagnostic.code.as_ref().map(String::as_str);
+                let rule_index = rule_id.and_then(|id| rules.binary_search(&id).ok());
+                SarifResult::new(files, rule_id, rule_index, diagnostic)
+            })
+            .collect();
+
+        SarifLog {
+            schema: SCHEMA,
+            version: VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: &config.tool_name,
+                        version: config.tool_version.as_ref().map(String::as_str),
+                        information_uri: config.tool_information_uri.as_ref().map(String::as_str),
+                        rules: rules
+                            .into_iter()
+                            .map(|id| ReportingDescriptor { id })
+                            .collect(),
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results,
+            }],
+        }
+    }
+}
+
+impl<'a> SarifResult<'a> {
+    fn new<'files, F>(
+        files: &'files F,
+        rule_id: Option<&'a str>,
+        rule_index: Option<usize>,
+        diagnostic: &'a Diagnostic<F::FileId>,
+    ) -> SarifResult<'a>
+    where
+        F: Files<'files>,
+    {
+        // Notes are appended to the message, as SARIF has no equivalent
+        let text = diagnostic.message_with_notes();
+
+        let (primary_labels, secondary_labels) = diagnostic
+            .labels
+            .iter()
+            .partition::<Vec<_>, _>(|label| label.style == LabelStyle::Primary);
+
+        let locations = primary_labels
+            .into_iter()
+            .map(|label| Location::new(files, None, label, None))
+            .collect();
+
+        // Secondary labels, and the labels of child diagnostics
+        let child_labels = diagnostic.children.iter().flat_map(|child| {
+            child
+                .labels
+                .iter()
+                .map(move |label| (label, Some(child.message.as_str())))
+        });
+        let related_locations = secondary_labels
+            .into_iter()
+            .map(|label| (label, None))
+            .chain(child_labels)
+            .enumerate()
+            .map(|(id, (label, fallback))| Location::new(files, Some(id), label, fallback))
+            .collect();
+
+        let fixes = diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| Fix::new(files, suggestion))
+            .collect();
+
+        SarifResult {
+            rule_id,
+            rule_index,
+            level: level(diagnostic.severity),
+            message: Message { text },
+            locations,
+            related_locations,
+            fixes,
+        }
+    }
+}
+
+impl Location {
+    fn new<'files, F>(
+        files: &'files F,
+        id: Option<usize>,
+        label: &Label<F::FileId>,
+        fallback_message: Option<&str>,
+    ) -> Location
+    where
+        F: Files<'files>,
+    {
+        let message = match label.message.as_str() {
+            "" => fallback_message,
+            message => Some(message),
+        };
+
+        Location {
+            id,
+            physical_location: PhysicalLocation {
+                artifact_location: artifact_location(files, label.file_id),
+                region: label
+                    .range
+                    .as_ref()
+                    .map(|range| region(files, label.file_id, range)),
+            },
+            message: message.map(|text| Message {
+                text: text.to_owned(),
+            }),
+        }
+    }
+}
+
+impl<'a> Fix<'a> {
+    fn new<'files, F>(files: &'files F, suggestion: &'a Suggestion<F::FileId>) -> Fix<'a>
+    where
+        F: Files<'files>,
+    {
+        let description = match suggestion.message.as_str() {
+            "" => None,
+            text => Some(Message {
+                text: text.to_owned(),
+            }),
+        };
+
+        Fix {
+            description,
+            artifact_changes: vec![ArtifactChange {
+                artifact_location: artifact_location(files, suggestion.file_id),
+                replacements: vec![Replacement {
+                    deleted_region: region(files, suggestion.file_id, &suggestion.range),
+                    inserted_content: ArtifactContent {
+                        text: &suggestion.replacement,
+                    },
+                }],
+            }],
+        }
+    }
+}
+
+/// The SARIF level of a severity.
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "note",
+    }
+}
+
+fn artifact_location<'files, F: Files<'files>>(
+    files: &'files F,
+    file_id: F::FileId,
+) -> ArtifactLocation {
+    let origin = files.origin(file_id).expect("origin");
+
+    ArtifactLocation {
+        uri: uri_reference(&origin.to_string()),
+    }
+}
+
+fn region<'files, F: Files<'files>>(
+    files: &'files F,
+    file_id: F::FileId,
+    range: &Range<usize>,
+) -> Region {
+    let start_line_index = files
+        .line_index(file_id, range.start)
+        .expect("start_line_index");
+    let end_line_index = files
+        .line_index(file_id, range.end)
+        .expect("end_line_index");
+    let start_line = files.line(file_id, start_line_index).expect("start_line");
+    let end_line = files.line(file_id, end_line_index).expect("end_line");
+
+    Region {
+        start_line: start_line.number,
+        start_column: start_line.column_number(range.start),
+        end_line: end_line.number,
+        end_column: end_line.column_number(range.end),
+        byte_offset: range.start,
+        byte_length: range.end - range.start,
+    }
+}
+
+/// Convert a file path to a URI reference, percent-encoding any characters
+/// that are not allowed to appear in a path.
+///
+/// Paths are converted to relative references, except for Windows paths that
+/// start with a drive letter, which are converted to `file` URIs, as the colon
+/// after the drive letter can't appear in the first segment of a relative
+/// reference.
+fn uri_reference(path: &str) -> String {
+    let mut uri = String::with_capacity(path.len());
+
+    let bytes = path.as_bytes();
+    let has_drive = bytes.len() >= 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes.len() == 2 || bytes[2] == b'/' || bytes[2] == b'\\');
+    let path = if has_drive {
+        uri.push_str("file:///");
+        uri.push_str(&path[..2]);
+        &path[2..]
+    } else {
+        path
+    };
+
+    for byte in path.bytes() {
+        match byte {
+            b'\\' => uri.push('/'),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => uri.push(byte as char),
+            b'-' | b'.' | b'_' | b'~' | b'/' | b'@' | b'!' | b'$' | b'&' | b'\'' => {
+                uri.push(byte as char)
+            }
+            b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_references() {
+        assert_eq!(uri_reference("src/main.fun"), "src/main.fun");
+        assert_eq!(uri_reference("src\\main.fun"), "src/main.fun");
+        assert_eq!(uri_reference("my file%.fun"), "my%20file%25.fun");
+        assert_eq!(uri_reference("λ.fun"), "%CE%BB.fun");
+        assert_eq!(uri_reference("a:b.fun"), "a%3Ab.fun");
+    }
+
+    #[test]
+    fn windows_uri_references() {
+        assert_eq!(
+            uri_reference("C:\\src\\main.fun"),
+            "file:///C:/src/main.fun"
+        );
+        assert_eq!(
+            uri_reference("d:/my src/main.fun"),
+            "file:///d:/my%20src/main.fun"
+        );
+        assert_eq!(uri_reference("C:"), "file:///C:");
+    }
+
+    #[test]
+    fn levels() {
+        assert_eq!(level(Severity::Bug), "error");
+        assert_eq!(level(Severity::Error), "error");
+        assert_eq!(level(Severity::Warning), "warning");
+        assert_eq!(level(Severity::Note), "note");
+        assert_eq!(level(Severity::Help), "note");
+    }
+}
@@ -1,5 +1,6 @@
 //! Terminal back-end for emitting diagnostics.
 
+use std::borrow::Cow;
 use std::io;
 use std::str::FromStr;
 use termcolor::{ColorChoice, WriteColor};
@@ -8,11 +9,23 @@ use crate::diagnostic::Diagnostic;
 use crate::files::Files;
 
 mod config;
+mod emitter;
+mod highlighter;
+#[cfg(feature = "terminal_size")]
+mod terminal_width;
+mod theme;
 mod views;
 
 pub use termcolor;
 
-pub use self::config::{Chars, Config, DisplayStyle, Styles};
+pub(crate) use self::config::percent_encode;
+pub use self::config::{Chars, Config, DisplayStyle, EscapeStyle, Hyperlinks, Styles};
+pub use self::emitter::Emitter;
+pub use self::highlighter::Highlighter;
+#[cfg(feature = "terminal_size")]
+pub use self::terminal_width::terminal_width;
+pub use self::theme::{Preset, Theme, ThemeError};
+pub(crate) use self::views::severity_name;
 
 /// A command line argument that configures the coloring of the output.
 ///
@@ -82,14 +95,111 @@ pub fn emit<'files, F: Files<'files>>(
     files: &'files F,
     diagnostic: &Diagnostic<F::FileId>,
 ) -> io::Result<()> {
-    use self::views::{RichDiagnostic, ShortDiagnostic};
+    emit_with_highlighter(writer, config, files, None, diagnostic)
+}
+
+/// Emit a diagnostic using the given writer, context, config, and files,
+/// highlighting the source code in snippets with the given highlighter.
+///
+/// Highlighting is skipped when the writer doesn't support colors.
+pub fn emit_highlighted<'files, F: Files<'files>>(
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    files: &'files F,
+    highlighter: &dyn Highlighter<F::FileId>,
+    diagnostic: &Diagnostic<F::FileId>,
+) -> io::Result<()> {
+    emit_with_highlighter(writer, config, files, Some(highlighter), diagnostic)
+}
 
+fn emit_with_highlighter<'files, F: Files<'files>>(
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    files: &'files F,
+    highlighter: Option<&dyn Highlighter<F::FileId>>,
+    diagnostic: &Diagnostic<F::FileId>,
+) -> io::Result<()> {
+    use self::views::{GccDiagnostic, QuickfixDiagnostic, RichDiagnostic, ShortDiagnostic};
+
+    let config = &*display_config(config);
     match config.display_style {
-        DisplayStyle::Rich => RichDiagnostic::new(diagnostic).emit(files, writer, config),
+        DisplayStyle::Rich | DisplayStyle::Rustc => RichDiagnostic::new(diagnostic)
+            .with_highlighter(highlighter)
+            .emit(files, writer, config),
         DisplayStyle::Short => ShortDiagnostic::new(diagnostic).emit(files, writer, config),
+        DisplayStyle::Gcc => GccDiagnostic::new(diagnostic)
+            .with_highlighter(highlighter)
+            .emit(files, writer, config),
+        DisplayStyle::Vim | DisplayStyle::Emacs => {
+            QuickfixDiagnostic::new(diagnostic).emit(files, writer, config)
+        }
     }
 }
 
+/// Emit the source snippets of a diagnostic's labels, laid out in the same
+/// way as `DisplayStyle::Rich`, for the renderers that provide their own
+/// headers and notes.
+pub(crate) fn emit_snippets<'files, F: Files<'files>>(
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+) -> io::Result<()> {
+    let config = &*display_config(config);
+    self::views::RichDiagnostic::new(diagnostic)
+        .snippets_only()
+        .emit(files, writer, config)
+}
+
+/// The config used to render diagnostics, with the characters of the display
+/// styles that mimic other compilers if the characters were left unchanged.
+fn display_config(config: &Config) -> Cow<'_, Config> {
+    let chars = match config.display_style {
+        DisplayStyle::Rustc => Chars::rustc(),
+        DisplayStyle::Gcc => Chars::gcc(),
+        DisplayStyle::Rich | DisplayStyle::Short | DisplayStyle::Vim | DisplayStyle::Emacs => {
+            return Cow::Borrowed(config);
+        }
+    };
+
+    if config.chars != Chars::default() {
+        return Cow::Borrowed(config);
+    }
+    Cow::Owned(Config {
+        chars,
+        ..config.clone()
+    })
+}
+
+/// Emit the extended explanation of a diagnostic code using the given writer
+/// and config.
+///
+/// Explanations are written in a subset of Markdown: `#` headings and inline
+/// code spans are highlighted, and the contents of fenced code blocks are
+/// indented and highlighted.
+///
+/// # Example
+///
+/// ```rust
+/// use codespan_reporting::explain::Registry;
+/// use codespan_reporting::term::termcolor::NoColor;
+/// use codespan_reporting::term::{self, Config};
+///
+/// let registry = Registry::new().with_explanation("E0001", "# Unexpected type");
+/// let mut writer = NoColor::new(Vec::new());
+///
+/// if let Some(explanation) = registry.get("E0001") {
+///     term::emit_explanation(&mut writer, &Config::default(), explanation).unwrap();
+/// }
+/// ```
+pub fn emit_explanation(
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    explanation: &str,
+) -> io::Result<()> {
+    self::views::Explanation::new(explanation).emit(writer, config)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,4 +217,24 @@ mod tests {
 
         emit(&mut writer, &Config::default(), &files, &diagnostic).unwrap();
     }
+
+    #[test]
+    fn display_config_keeps_custom_chars() {
+        let config = Config {
+            display_style: DisplayStyle::Rustc,
+            ..Config::default()
+        };
+        assert_eq!(display_config(&config).chars, Chars::rustc());
+
+        let chars = Chars {
+            primary_caret: '!',
+            ..Chars::default()
+        };
+        let config = Config {
+            display_style: DisplayStyle::Gcc,
+            chars: chars.clone(),
+            ..Config::default()
+        };
+        assert_eq!(display_config(&config).chars, chars);
+    }
 }
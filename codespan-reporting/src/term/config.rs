@@ -1,7 +1,9 @@
 use std::io;
-use termcolor::{Color, ColorSpec};
+use std::path::PathBuf;
+use termcolor::{Color, ColorSpec, WriteColor};
 
 use crate::diagnostic::Severity;
+use crate::files::{ColumnUnit, Line};
 
 /// Configures how a diagnostic is rendered.
 #[derive(Clone, Debug)]
@@ -9,13 +11,55 @@ pub struct Config {
     /// The display style to use when rendering diagnostics.
     /// Defaults to: `DisplayStyle::Rich`.
     pub display_style: DisplayStyle,
-    /// Column width of tabs.
+    /// The number of columns between tab stops. Tabs in source code are
+    /// expanded to the next tab stop.
     /// Defaults to: `4`.
     pub tab_width: usize,
     /// Styles to use when rendering the diagnostic.
     pub styles: Styles,
     /// Characters to use when rendering the diagnostic.
     pub chars: Chars,
+    /// The command that prints the extended explanation of a diagnostic code,
+    /// for example `"mytool --explain"`. When set, rich diagnostics with codes
+    /// end with a hint suggesting that the command be run.
+    /// Defaults to: `None`.
+    pub explain_command: Option<String>,
+    /// The number of lines of source code to show before each labelled line.
+    /// Defaults to: `0`.
+    pub before_label_lines: usize,
+    /// The number of lines of source code to show after each labelled line.
+    /// Defaults to: `0`.
+    pub after_label_lines: usize,
+    /// The maximum number of consecutive lines to show away from any labels,
+    /// for example in the middle of a multi-line label. Longer stretches of
+    /// source code are collapsed into a single `...` line.
+    /// Defaults to: `3`.
+    pub max_unlabelled_lines: usize,
+    /// The maximum width of the output, in display columns. Long lines of
+    /// source code are truncated around their labels, and long messages are
+    /// wrapped at word boundaries. The width of the terminal can be found
+    /// using `term::terminal_width`, with the `terminal_size` feature.
+    /// Defaults to: `None`.
+    pub max_width: Option<usize>,
+    /// How to display control, invisible, and bidirectional formatting
+    /// characters in source code, which could otherwise corrupt the terminal
+    /// or disguise the code that is shown.
+    /// Defaults to: `EscapeStyle::Verbatim`.
+    pub escape_style: EscapeStyle,
+    /// The unit that column numbers are counted in when reporting locations.
+    /// Defaults to: `ColumnUnit::Char`.
+    pub column_unit: ColumnUnit,
+    /// Whether to report column numbers in display columns, as shown in the
+    /// rendered source code, instead of counting them in `column_unit`. Tabs
+    /// are expanded to the next tab stop, and wide characters count as two
+    /// columns.
+    /// Defaults to: `false`.
+    pub report_display_columns: bool,
+    /// Hyperlinks to add to loci and diagnostic codes, which are displayed as
+    /// clickable links by terminals that support OSC 8 escape sequences. They
+    /// are only written when the writer supports hyperlinks.
+    /// Defaults to: `None`.
+    pub hyperlinks: Option<Hyperlinks>,
 }
 
 impl Default for Config {
@@ -25,21 +69,67 @@ impl Default for Config {
             tab_width: 4,
             styles: Styles::default(),
             chars: Chars::default(),
+            explain_command: None,
+            before_label_lines: 0,
+            after_label_lines: 0,
+            max_unlabelled_lines: 3,
+            max_width: None,
+            escape_style: EscapeStyle::Verbatim,
+            column_unit: ColumnUnit::Char,
+            report_display_columns: false,
+            hyperlinks: None,
         }
     }
 }
 
 impl Config {
-    /// Measure the width of a string, taking into account the tab width.
+    /// Measure the width of a string, taking into account tab stops and any
+    /// escaped characters. Grapheme clusters, such as emoji sequences and
+    /// characters followed by combining marks, are measured as a whole.
     pub fn width(&self, s: &str) -> usize {
-        use unicode_width::UnicodeWidthChar;
+        self.width_at(0, s)
+    }
 
-        s.chars()
-            .map(|ch| match ch {
-                '\t' => self.tab_width,
-                _ => ch.width().unwrap_or(0),
-            })
-            .sum()
+    /// Measure the width of a string that starts at the given display column,
+    /// which determines how far its tabs extend to reach the next tab stop.
+    pub fn width_at(&self, column: usize, s: &str) -> usize {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let end_column = s.graphemes(true).fold(column, |column, grapheme| {
+            column
+                + match grapheme {
+                    "\t" => tab_stop_width(self.tab_width, column),
+                    _ => grapheme_width(self.escape_style, grapheme),
+                }
+        });
+        end_column - column
+    }
+
+    /// The 1-indexed column number at the given byte index in a line, counted
+    /// in display columns when `report_display_columns` is set, or in the
+    /// configured `column_unit` otherwise.
+    pub fn column_number(&self, line: &Line<impl AsRef<str>>, byte_index: usize) -> usize {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        if !self.report_display_columns {
+            return line.column_number_in(byte_index, self.column_unit);
+        }
+
+        // Count the display columns before the grapheme cluster that
+        // contains the byte index
+        let source = line.source.as_ref();
+        let relative_index = byte_index.saturating_sub(line.start);
+        let prefix_len = if relative_index < source.len() {
+            source
+                .grapheme_indices(true)
+                .map(|(index, _)| index)
+                .take_while(|index| *index <= relative_index)
+                .last()
+                .unwrap_or(0)
+        } else {
+            source.len()
+        };
+        self.width(&source[..prefix_len]) + 1
     }
 
     /// Construct a source writer using the current config.
@@ -47,30 +137,104 @@ impl Config {
         SourceWriter {
             writer,
             tab_width: self.tab_width,
+            escape_style: self.escape_style,
+            column: 0,
         }
     }
 }
 
-/// Writer that replaces tab characters with the configured number of spaces.
+/// The number of columns that a tab at the given display column extends, to
+/// reach the next tab stop.
+fn tab_stop_width(tab_width: usize, column: usize) -> usize {
+    match tab_width {
+        0 => 0,
+        _ => tab_width - column % tab_width,
+    }
+}
+
+/// Measure the width of a grapheme cluster other than a tab.
+fn grapheme_width(escape_style: EscapeStyle, grapheme: &str) -> usize {
+    use unicode_width::UnicodeWidthChar;
+
+    // Escaped characters are displayed separately from the rest of their
+    // cluster
+    if grapheme.chars().any(|ch| escaped_char_kind(ch).is_some()) {
+        return grapheme
+            .chars()
+            .map(|ch| match escape_style.escape(ch) {
+                Some(escaped) => escaped.chars().count(),
+                None => ch.width().unwrap_or(0),
+            })
+            .sum();
+    }
+
+    let width = grapheme
+        .chars()
+        .map(|ch| ch.width().unwrap_or(0))
+        .max()
+        .unwrap_or(0);
+    // Emoji presentation selectors and flags are displayed as wide emoji
+    let is_regional_indicator = |ch| ('\u{1F1E6}'..='\u{1F1FF}').contains(&ch);
+    let is_emoji = grapheme.contains('\u{FE0F}')
+        || (grapheme.chars().count() == 2 && grapheme.chars().all(is_regional_indicator));
+    if is_emoji {
+        std::cmp::max(width, 2)
+    } else {
+        width
+    }
+}
+
+/// Writer that expands tab characters to the next tab stop, and escapes
+/// characters according to the configured escape style.
 pub struct SourceWriter<W> {
     writer: W,
     tab_width: usize,
+    escape_style: EscapeStyle,
+    /// The display column that the next character is written at, counted
+    /// from the start of the writer or from the last line break.
+    column: usize,
 }
 
 impl<W: io::Write> io::Write for SourceWriter<W> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        // Only valid UTF-8 can be escaped, so any trailing invalid bytes are
+        // written as they are
+        let text = match std::str::from_utf8(buf) {
+            Ok(text) => text,
+            Err(error) => std::str::from_utf8(&buf[..error.valid_up_to()]).unwrap_or(""),
+        };
+
         let mut last_term = 0usize;
-        for (i, ch) in buf.iter().enumerate() {
-            if *ch == b'\t' {
+        for (i, grapheme) in text.grapheme_indices(true) {
+            if grapheme == "\t" {
                 self.writer.write_all(&buf[last_term..i])?;
                 last_term = i + 1;
-                write!(
-                    self.writer,
-                    "{space: >width$}",
-                    space = "",
-                    width = self.tab_width,
-                )?;
+                let width = tab_stop_width(self.tab_width, self.column);
+                write!(self.writer, "{space: >width$}", space = "", width = width)?;
+                self.column += width;
+                continue;
             }
+
+            if grapheme
+                .chars()
+                .any(|ch| self.escape_style.escape(ch).is_some())
+            {
+                self.writer.write_all(&buf[last_term..i])?;
+                last_term = i + grapheme.len();
+                for ch in grapheme.chars() {
+                    match self.escape_style.escape(ch) {
+                        Some(escaped) => write!(self.writer, "{}", escaped)?,
+                        None => write!(self.writer, "{}", ch)?,
+                    }
+                }
+            }
+            self.column = if grapheme.ends_with('\n') {
+                0
+            } else {
+                self.column + grapheme_width(self.escape_style, grapheme)
+            };
         }
         self.writer.write_all(&buf[last_term..])?;
         Ok(buf.len())
@@ -81,8 +245,26 @@ impl<W: io::Write> io::Write for SourceWriter<W> {
     }
 }
 
+impl<W: WriteColor> WriteColor for SourceWriter<W> {
+    fn supports_color(&self) -> bool {
+        self.writer.supports_color()
+    }
+
+    fn supports_hyperlinks(&self) -> bool {
+        self.writer.supports_hyperlinks()
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        self.writer.set_color(spec)
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        self.writer.reset()
+    }
+}
+
 /// The display style to use when rendering diagnostics.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum DisplayStyle {
     /// Output a richly formatted diagnostic, with source code previews.
     ///
@@ -108,6 +290,241 @@ pub enum DisplayStyle {
     /// error[E0002]: Bad config found
     /// ```
     Short,
+    /// Output a diagnostic in the layout used by rustc, with source code
+    /// previews that are introduced by `-->` and bordered with `|`. This
+    /// style uses the characters from `Chars::rustc`, unless `Config::chars`
+    /// has been changed from its default.
+    ///
+    /// ```text
+    /// error[E0001]: unexpected type in `+` application
+    ///  --> test:2:9
+    ///   |
+    /// 2 | (+ test "")
+    ///   |         ^^ expected `Int` but found `String`
+    ///   |
+    ///   = note: expected type `Int`
+    ///              found type `String`
+    ///
+    /// ```
+    Rustc,
+    /// Output a diagnostic in the layout used by GCC and Clang, with a
+    /// located header for each primary label, followed by its source line and
+    /// a range marker. Secondary labels on other lines are shown as notes.
+    /// This style uses the characters from `Chars::gcc`, unless
+    /// `Config::chars` has been changed from its default.
+    ///
+    /// ```text
+    /// test:2:9: error: unexpected type in `+` application [E0001]
+    ///     2 | (+ test "")
+    ///       |         ^~ expected `Int` but found `String`
+    /// note: expected type `Int`
+    ///      found type `String`
+    /// ```
+    Gcc,
+    /// Output a line for each label, in a format that Vim's default
+    /// `errorformat` can parse with `:cfile`, followed by indented lines for
+    /// label messages and notes. Secondary labels are located notes, and
+    /// child diagnostics without labels are located at their parent.
+    ///
+    /// ```text
+    /// test:2:9: error[E0001]: unexpected type in `+` application
+    ///     = expected `Int` but found `String`
+    ///     = expected type `Int`
+    ///          found type `String`
+    /// test:1:2: note: based on the definition of `+`
+    /// ```
+    Vim,
+    /// Output a line for each label, in a format that Emacs' compilation
+    /// mode can parse, followed by indented lines for label messages and
+    /// notes. The locations include the inclusive end of each label, in the
+    /// format of the GNU coding standards. Child diagnostics without labels
+    /// are located at their parent.
+    ///
+    /// ```text
+    /// test:2.9-10: error[E0001]: unexpected type in `+` application
+    ///     = expected `Int` but found `String`
+    ///     = expected type `Int`
+    ///          found type `String`
+    /// test:1.2-2: note: based on the definition of `+`
+    /// ```
+    Emacs,
+}
+
+/// Configures the hyperlinks that are added to diagnostics.
+#[derive(Clone, Debug, Default)]
+pub struct Hyperlinks {
+    /// A template for the URLs that loci link to, where `{path}` is replaced
+    /// by the absolute path of the file, and `{line}` and `{col}` by the line
+    /// and column numbers. For example `"vscode://file/{path}:{line}:{col}"`
+    /// opens the location in Visual Studio Code.
+    /// Defaults to: `None`, linking loci to `file://` URLs.
+    pub locus_template: Option<String>,
+    /// The directory that relative paths of files are resolved against, for
+    /// example the current directory of the process.
+    /// Defaults to: `None`, leaving the loci of relative paths unlinked.
+    pub base_dir: Option<PathBuf>,
+    /// A template for the URLs that diagnostic codes link to, where `{code}`
+    /// is replaced by the code, for example
+    /// `"https://example.com/errors/{code}.html"`.
+    /// Defaults to: `None`, leaving codes unlinked.
+    pub code_template: Option<String>,
+}
+
+impl Hyperlinks {
+    /// Link loci to `file://` URLs, leaving diagnostic codes unlinked.
+    pub fn new() -> Hyperlinks {
+        Hyperlinks::default()
+    }
+
+    /// Link loci using the given URL template.
+    pub fn with_locus_template(mut self, template: impl Into<String>) -> Hyperlinks {
+        self.locus_template = Some(template.into());
+        self
+    }
+
+    /// Resolve relative paths of files against the given directory.
+    pub fn with_base_dir(mut self, base_dir: impl Into<PathBuf>) -> Hyperlinks {
+        self.base_dir = Some(base_dir.into());
+        self
+    }
+
+    /// Link diagnostic codes using the given URL template.
+    pub fn with_code_template(mut self, template: impl Into<String>) -> Hyperlinks {
+        self.code_template = Some(template.into());
+        self
+    }
+
+    /// The URL that a locus links to, if its path is absolute or can be
+    /// resolved against the base directory. Loci that refer to a whole file
+    /// link to its first line and column.
+    pub fn locus_url(&self, origin: &str, position: Option<(usize, usize)>) -> Option<String> {
+        let path = self.absolute_url_path(origin)?;
+        let (line_number, column_number) = position.unwrap_or((1, 1));
+
+        let url = match &self.locus_template {
+            None if path.starts_with('/') => format!("file://{}", path),
+            // Windows paths, such as `C:/src/main.rs`
+            None => format!("file:///{}", path),
+            Some(template) => template
+                .replace("{path}", &path)
+                .replace("{line}", &line_number.to_string())
+                .replace("{col}", &column_number.to_string()),
+        };
+        Some(url)
+    }
+
+    /// The URL that a diagnostic code links to, if any.
+    pub fn code_url(&self, code: &str) -> Option<String> {
+        let template = self.code_template.as_ref()?;
+        Some(template.replace("{code}", &percent_encode(code)))
+    }
+
+    /// The absolute path of a file, with forward slashes and
+    /// percent-encoding, for use in URLs.
+    fn absolute_url_path(&self, origin: &str) -> Option<String> {
+        let path = std::path::Path::new(origin);
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.base_dir.as_ref()?.join(path)
+        };
+
+        Some(percent_encode(&path.to_string_lossy().replace('\\', "/")))
+    }
+}
+
+/// Percent-encode the bytes of some text that aren't allowed in URL paths.
+pub(crate) fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// How to display characters in source code that are invisible, or that
+/// change how the surrounding text is displayed: control characters such as
+/// `\x1b` and `\r`, invisible characters such as zero-width spaces, and the
+/// bidirectional formatting characters used to reorder text. Tabs and newlines
+/// are never escaped.
+///
+/// ```text
+/// 2 │     if access_level != "user␛[0m" { // Check if admin
+/// 3 │     /*<U+202E> } <U+2066>if (is_admin)<U+2069> <U+2066> begin admins only */
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EscapeStyle {
+    /// Write the characters as they are.
+    Verbatim,
+    /// Replace control characters with their Unicode control pictures, such
+    /// as `␛`, and the remaining characters with their code points, such as
+    /// `<U+202E>`.
+    Pictures,
+    /// Replace the characters with their code points, such as `<U+202E>`.
+    CodePoints,
+}
+
+impl EscapeStyle {
+    /// The text that replaces a character, or `None` if the character is
+    /// displayed as it is.
+    pub fn escape(self, ch: char) -> Option<String> {
+        escaped_char_kind(ch)?;
+        match self {
+            EscapeStyle::Verbatim => None,
+            EscapeStyle::Pictures => match ch {
+                '\u{0}'..='\u{1f}' => std::char::from_u32(0x2400 + ch as u32).map(String::from),
+                '\u{7f}' => Some("\u{2421}".to_owned()),
+                _ => Some(format!("<U+{:04X}>", ch as u32)),
+            },
+            EscapeStyle::CodePoints => Some(format!("<U+{:04X}>", ch as u32)),
+        }
+    }
+}
+
+/// A description of the kind of character that is escaped by an
+/// `EscapeStyle`, or `None` if the character is never escaped.
+pub fn escaped_char_kind(ch: char) -> Option<&'static str> {
+    match ch {
+        '\t' | '\n' => None,
+        '\u{0}'..='\u{1f}' | '\u{7f}'..='\u{9f}' => Some("control"),
+        '\u{61c}' | '\u{200e}' | '\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' => {
+            Some("bidirectional formatting")
+        }
+        '\u{200b}' | '\u{2060}'..='\u{2064}' | '\u{feff}' => Some("invisible"),
+        _ => None,
+    }
+}
+
+/// Implement lookups of the fields of a config struct by name, for loading
+/// them from themes.
+macro_rules! impl_field_names {
+    ($Struct:ident, $Field:ty, [$($name:ident),* $(,)?]) => {
+        impl $Struct {
+            /// The names of the fields, in the order they are declared.
+            pub const NAMES: &'static [&'static str] = &[$(stringify!($name)),*];
+
+            /// The field with the given name.
+            pub fn by_name(&self, name: &str) -> Option<&$Field> {
+                match name {
+                    $(stringify!($name) => Some(&self.$name),)*
+                    _ => None,
+                }
+            }
+
+            /// The field with the given name, for modification.
+            pub fn by_name_mut(&mut self, name: &str) -> Option<&mut $Field> {
+                match name {
+                    $(stringify!($name) => Some(&mut self.$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
 }
 
 /// Styles to use when rendering the diagnostic.
@@ -160,6 +577,20 @@ pub struct Styles {
     /// The style to use when rendering the note bullets.
     /// Defaults `fg:blue` (or `fg:cyan` on windows).
     pub note_bullet: ColorSpec,
+
+    /// The style to use when rendering text inserted by a suggestion.
+    /// Defaults to `fg:green`.
+    pub suggestion_insertion: ColorSpec,
+    /// The style to use when rendering text removed by a suggestion.
+    /// Defaults to `fg:red`.
+    pub suggestion_removal: ColorSpec,
+
+    /// The style to use when rendering headings in explanations.
+    /// Defaults to `bold intense underline`.
+    pub explanation_heading: ColorSpec,
+    /// The style to use when rendering code in explanations.
+    /// Defaults to `fg:yellow`.
+    pub explanation_code: ColorSpec,
 }
 
 impl Styles {
@@ -207,10 +638,44 @@ impl Styles {
             line_number: ColorSpec::new().set_fg(Some(blue)).clone(),
             source_border: ColorSpec::new().set_fg(Some(blue)).clone(),
             note_bullet: ColorSpec::new().set_fg(Some(blue)).clone(),
+
+            suggestion_insertion: ColorSpec::new().set_fg(Some(Color::Green)).clone(),
+            suggestion_removal: ColorSpec::new().set_fg(Some(Color::Red)).clone(),
+
+            explanation_heading: (ColorSpec::new().set_bold(true).set_intense(true))
+                .set_underline(true)
+                .clone(),
+            explanation_code: ColorSpec::new().set_fg(Some(Color::Yellow)).clone(),
         }
     }
 }
 
+impl_field_names!(
+    Styles,
+    ColorSpec,
+    [
+        header_bug,
+        header_error,
+        header_warning,
+        header_note,
+        header_help,
+        header_message,
+        primary_label_bug,
+        primary_label_error,
+        primary_label_warning,
+        primary_label_note,
+        primary_label_help,
+        secondary_label,
+        line_number,
+        source_border,
+        note_bullet,
+        suggestion_insertion,
+        suggestion_removal,
+        explanation_heading,
+        explanation_code,
+    ]
+);
+
 impl Default for Styles {
     fn default() -> Styles {
         // Blue is really difficult to see on the standard windows command line
@@ -224,7 +689,7 @@ impl Default for Styles {
 }
 
 /// Characters to use when rendering the diagnostic.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Chars {
     /// The character to use for the top-left border of the source.
     /// Defaults to: `'┌'`.
@@ -271,6 +736,21 @@ pub struct Chars {
     /// The character to use for the left of a multi-line label.
     /// Defaults to: `'│'`.
     pub multiline_left: char,
+
+    /// The character to use for the pointers from underlines to the messages
+    /// of labels that could not be displayed inline.
+    /// Defaults to: `'│'`.
+    pub pointer_left: char,
+
+    /// The character to use for marking text inserted by a suggestion.
+    /// Defaults to: `'+'`.
+    pub suggestion_insertion: char,
+    /// The character to use for marking text replaced by a suggestion.
+    /// Defaults to: `'~'`.
+    pub suggestion_replacement: char,
+    /// The character to use for marking text removed by a suggestion.
+    /// Defaults to: `'-'`.
+    pub suggestion_removal: char,
 }
 
 impl Default for Chars {
@@ -293,6 +773,79 @@ impl Default for Chars {
             multiline_bottom_left: '╰',
             multiline_bottom: '─',
             multiline_left: '│',
+
+            pointer_left: '│',
+
+            suggestion_insertion: '+',
+            suggestion_replacement: '~',
+            suggestion_removal: '-',
+        }
+    }
+}
+
+impl Chars {
+    /// The ASCII characters used by rustc, as shown by `DisplayStyle::Rustc`.
+    pub fn rustc() -> Chars {
+        Chars {
+            source_border_top_left: '-',
+            source_border_top: '-',
+            source_border_left: '|',
+            source_border_left_break: '.',
+
+            note_bullet: '=',
+
+            primary_caret: '^',
+            secondary_caret: '-',
+
+            multiline_primary_caret: '^',
+            multiline_secondary_caret: '-',
+            multiline_top_left: '/',
+            multiline_top: '_',
+            multiline_bottom_left: '|',
+            multiline_bottom: '_',
+            multiline_left: '|',
+
+            pointer_left: '|',
+
+            suggestion_insertion: '+',
+            suggestion_replacement: '~',
+            suggestion_removal: '-',
+        }
+    }
+
+    /// The ASCII characters used by GCC and Clang, as shown by
+    /// `DisplayStyle::Gcc`. The secondary caret is also used for the columns
+    /// of primary marks that follow their first column, like `^~~~`.
+    pub fn gcc() -> Chars {
+        Chars {
+            secondary_caret: '~',
+            multiline_secondary_caret: '~',
+            ..Chars::rustc()
         }
     }
 }
+
+impl_field_names!(
+    Chars,
+    char,
+    [
+        source_border_top_left,
+        source_border_top,
+        source_border_left,
+        source_border_left_break,
+        note_bullet,
+        primary_caret,
+        secondary_caret,
+        multiline_primary_caret,
+        multiline_secondary_caret,
+        multiline_top_left,
+        multiline_top,
+        multiline_bottom_left,
+        multiline_bottom,
+        multiline_left,
+        pointer_left,
+        suggestion_insertion,
+        suggestion_replacement,
+        suggestion_removal,
+    ]
+);
@@ -0,0 +1,436 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io;
+use termcolor::WriteColor;
+
+use crate::diagnostic::{Diagnostic, LabelStyle, Severity};
+use crate::files::Files;
+use crate::lint::LintLevels;
+use crate::term::{emit, Config};
+
+use super::views::Header;
+
+/// A sink for diagnostics that emits them to a writer, keeping track of the
+/// number of diagnostics of each severity that were emitted.
+///
+/// The emitter can be configured to:
+///
+/// - adjust the severity of diagnostics according to their lint levels
+/// - stop emitting diagnostics once a maximum number of errors is reached
+/// - drop diagnostics that are identical to ones that were already emitted
+/// - buffer the diagnostics, emitting them sorted by their primary location
+///
+/// Once all of the diagnostics have been emitted, `Emitter::finish` should be
+/// called to flush any buffered diagnostics and to print a summary line:
+///
+/// ```text
+/// error: aborting due to 3 previous errors; 2 warnings emitted
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use codespan_reporting::diagnostic::{Diagnostic, Label};
+/// use codespan_reporting::files::SimpleFiles;
+/// use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+/// use codespan_reporting::term::{Config, Emitter};
+///
+/// let mut files = SimpleFiles::new();
+/// let file_id = files.add("test", "let x = y;");
+///
+/// let writer = StandardStream::stderr(ColorChoice::Auto);
+/// let mut emitter = Emitter::new(writer.lock(), Config::default(), &files).with_max_errors(10);
+///
+/// emitter.emit(Diagnostic::error()
+///     .with_message("unbound variable: `y`")
+///     .with_labels(vec![Label::primary(file_id, 8..9)]))?;
+///
+/// emitter.finish()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct Emitter<'files, W, F: Files<'files>> {
+    writer: W,
+    config: Config,
+    files: &'files F,
+    lint_levels: Option<LintLevels>,
+    max_errors: Option<usize>,
+    deduplicate: bool,
+    sort: bool,
+    counts: [usize; 5],
+    suppressed: usize,
+    /// The diagnostics that were already emitted, by a hash of their header.
+    seen: HashMap<u64, Vec<Diagnostic<F::FileId>>>,
+    diagnostics: Vec<Diagnostic<F::FileId>>,
+}
+
+impl<'files, W, F> Emitter<'files, W, F>
+where
+    W: WriteColor,
+    F: Files<'files>,
+{
+    /// Create a new emitter that deduplicates diagnostics, without an error
+    /// limit, and without sorting.
+    pub fn new(writer: W, config: Config, files: &'files F) -> Emitter<'files, W, F> {
+        Emitter {
+            writer,
+            config,
+            files,
+            lint_levels: None,
+            max_errors: None,
+            deduplicate: true,
+            sort: false,
+            counts: [0; 5],
+            suppressed: 0,
+            seen: HashMap::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Apply the given lint levels to diagnostics before they are emitted,
+    /// dropping the diagnostics of allowed lints.
+    pub fn with_lint_levels(mut self, lint_levels: LintLevels) -> Emitter<'files, W, F> {
+        self.lint_levels = Some(lint_levels);
+        self
+    }
+
+    /// Stop emitting diagnostics once the given number of errors have been
+    /// emitted.
+    pub fn with_max_errors(mut self, max_errors: usize) -> Emitter<'files, W, F> {
+        self.max_errors = Some(max_errors);
+        self
+    }
+
+    /// Configure whether diagnostics that are identical to previously emitted
+    /// diagnostics should be dropped.
+    pub fn with_deduplication(mut self, deduplicate: bool) -> Emitter<'files, W, F> {
+        self.deduplicate = deduplicate;
+        self
+    }
+
+    /// Configure whether diagnostics should be buffered until `finish` is
+    /// called, and then emitted in order of their primary location.
+    ///
+    /// Diagnostics are ordered by the origin of the file of their first
+    /// primary label, and then by the start of that label. Diagnostics without
+    /// primary labels are emitted last, in the order they were received.
+    pub fn with_sorting(mut self, sort: bool) -> Emitter<'files, W, F> {
+        self.sort = sort;
+        self
+    }
+
+    /// Emit a diagnostic, unless it is allowed by the lint levels, is a
+    /// duplicate, or the error limit has been reached.
+    pub fn emit(&mut self, diagnostic: Diagnostic<F::FileId>) -> io::Result<()> {
+        let diagnostic = match &self.lint_levels {
+            None => diagnostic,
+            Some(lint_levels) => match lint_levels.apply(diagnostic) {
+                None => return Ok(()),
+                Some(diagnostic) => diagnostic,
+            },
+        };
+
+        if self.deduplicate && !self.insert_seen(&diagnostic) {
+            return Ok(());
+        }
+        if self.limit_reached() {
+            self.suppressed += 1;
+            return Ok(());
+        }
+
+        self.counts[severity_index(diagnostic.severity)] += 1;
+
+        if self.sort {
+            self.diagnostics.push(diagnostic);
+        } else {
+            emit(&mut self.writer, &self.config, self.files, &diagnostic)?;
+        }
+
+        Ok(())
+    }
+
+    /// The number of diagnostics of the given severity that were emitted.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.counts[severity_index(severity)]
+    }
+
+    /// The number of bugs and errors that were emitted.
+    pub fn error_count(&self) -> usize {
+        self.count(Severity::Bug) + self.count(Severity::Error)
+    }
+
+    /// The number of warnings that were emitted.
+    pub fn warning_count(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    /// Returns `true` if any bugs or errors were emitted.
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    /// Returns `true` if the maximum number of errors has been reached, after
+    /// which no further diagnostics will be emitted.
+    pub fn limit_reached(&self) -> bool {
+        match self.max_errors {
+            Some(max_errors) => self.error_count() >= max_errors,
+            None => false,
+        }
+    }
+
+    /// The number of diagnostics that were dropped after the error limit was
+    /// reached.
+    pub fn suppressed_count(&self) -> usize {
+        self.suppressed
+    }
+
+    /// Emit any buffered diagnostics followed by a summary of the number of
+    /// errors and warnings, returning the underlying writer.
+    ///
+    /// No summary is printed if no errors or warnings were emitted.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.sort {
+            let files = self.files;
+            self.diagnostics.sort_by_cached_key(|diagnostic| {
+                let primary_label = diagnostic
+                    .labels
+                    .iter()
+                    .find(|label| label.style == LabelStyle::Primary);
+
+                match primary_label {
+                    Some(label) => {
+                        let origin = files.origin(label.file_id).map(|o| o.to_string());
+                        let start = label.range.as_ref().map_or(0, |range| range.start);
+                        (false, origin, start)
+                    }
+                    None => (true, None, 0),
+                }
+            });
+
+            for diagnostic in &self.diagnostics {
+                emit(&mut self.writer, &self.config, self.files, diagnostic)?;
+            }
+        }
+
+        if let Some((severity, message)) = self.summary() {
+            Header::new(severity, None, &message).emit(&mut self.writer, &self.config)?;
+        }
+
+        Ok(self.writer)
+    }
+
+    /// Record a diagnostic as seen, returning `false` if an identical
+    /// diagnostic was already seen.
+    fn insert_seen(&mut self, diagnostic: &Diagnostic<F::FileId>) -> bool {
+        let mut hasher = DefaultHasher::new();
+        (diagnostic.severity, &diagnostic.code, &diagnostic.message).hash(&mut hasher);
+
+        let seen = self.seen.entry(hasher.finish()).or_default();
+        if seen.contains(diagnostic) {
+            return false;
+        }
+        seen.push(diagnostic.clone());
+        true
+    }
+
+    /// The severity and message of the summary line.
+    fn summary(&self) -> Option<(Severity, String)> {
+        let warnings = match self.warning_count() {
+            0 => None,
+            1 => Some("1 warning emitted".to_owned()),
+            count => Some(format!("{} warnings emitted", count)),
+        };
+
+        match (self.error_count(), warnings) {
+            (0, None) => None,
+            (0, Some(warnings)) => Some((Severity::Warning, warnings)),
+            (errors, warnings) => {
+                let mut message = match errors {
+                    1 => "aborting due to previous error".to_owned(),
+                    errors => format!("aborting due to {} previous errors", errors),
+                };
+                if self.suppressed > 0 {
+                    message.push_str(" (error limit reached)");
+                }
+                if let Some(warnings) = warnings {
+                    message.push_str("; ");
+                    message.push_str(&warnings);
+                }
+                Some((Severity::Error, message))
+            }
+        }
+    }
+}
+
+fn severity_index(severity: Severity) -> usize {
+    match severity {
+        Severity::Bug => 0,
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Note => 3,
+        Severity::Help => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::diagnostic::Label;
+    use crate::files::SimpleFiles;
+    use crate::term::DisplayStyle;
+
+    fn short_config() -> Config {
+        Config {
+            display_style: DisplayStyle::Short,
+            ..Config::default()
+        }
+    }
+
+    fn finish<'files, F: Files<'files>>(
+        emitter: Emitter<'files, termcolor::NoColor<Vec<u8>>, F>,
+    ) -> String {
+        let writer = emitter.finish().unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn summary() {
+        let files = SimpleFiles::<&str, &str>::new();
+        let writer = termcolor::NoColor::new(Vec::new());
+        let mut emitter = Emitter::new(writer, short_config(), &files);
+
+        emitter.emit(Diagnostic::error().with_message("a")).unwrap();
+        emitter
+            .emit(Diagnostic::warning().with_message("b"))
+            .unwrap();
+        emitter.emit(Diagnostic::error().with_message("c")).unwrap();
+        emitter.emit(Diagnostic::note().with_message("d")).unwrap();
+        emitter.emit(Diagnostic::error().with_message("a")).unwrap();
+
+        assert_eq!(emitter.error_count(), 2);
+        assert_eq!(emitter.warning_count(), 1);
+        assert_eq!(emitter.count(Severity::Note), 1);
+        assert_eq!(
+            finish(emitter),
+            "error: a\n\
+             warning: b\n\
+             error: c\n\
+             note: d\n\
+             error: aborting due to 2 previous errors; 1 warning emitted\n",
+        );
+    }
+
+    #[test]
+    fn warnings_only_summary() {
+        let files = SimpleFiles::<&str, &str>::new();
+        let writer = termcolor::NoColor::new(Vec::new());
+        let mut emitter = Emitter::new(writer, short_config(), &files).with_deduplication(false);
+
+        emitter
+            .emit(Diagnostic::warning().with_message("a"))
+            .unwrap();
+        emitter
+            .emit(Diagnostic::warning().with_message("a"))
+            .unwrap();
+
+        assert!(!emitter.has_errors());
+        assert_eq!(
+            finish(emitter),
+            "warning: a\nwarning: a\nwarning: 2 warnings emitted\n"
+        );
+    }
+
+    #[test]
+    fn lint_levels() {
+        use crate::lint::Level;
+
+        let mut lint_levels = LintLevels::new();
+        lint_levels.add_group("style", vec!["W0001", "W0002"]);
+        lint_levels.set("W0001", Level::Deny).unwrap();
+        lint_levels.set("W0002", Level::Allow).unwrap();
+
+        let files = SimpleFiles::<&str, &str>::new();
+        let writer = termcolor::NoColor::new(Vec::new());
+        let mut emitter =
+            Emitter::new(writer, short_config(), &files).with_lint_levels(lint_levels);
+
+        emitter
+            .emit(Diagnostic::warning().with_code("W0001").with_message("a"))
+            .unwrap();
+        emitter
+            .emit(Diagnostic::warning().with_code("W0002").with_message("b"))
+            .unwrap();
+
+        assert_eq!(
+            finish(emitter),
+            "error[W0001]: a\nerror: aborting due to previous error\n",
+        );
+    }
+
+    #[test]
+    fn max_errors() {
+        let files = SimpleFiles::<&str, &str>::new();
+        let writer = termcolor::NoColor::new(Vec::new());
+        let mut emitter = Emitter::new(writer, short_config(), &files).with_max_errors(1);
+
+        emitter.emit(Diagnostic::error().with_message("a")).unwrap();
+        assert!(emitter.limit_reached());
+        emitter.emit(Diagnostic::error().with_message("b")).unwrap();
+        // Duplicates are dropped without being counted as suppressed
+        emitter.emit(Diagnostic::error().with_message("a")).unwrap();
+        emitter
+            .emit(Diagnostic::warning().with_message("c"))
+            .unwrap();
+
+        assert_eq!(emitter.suppressed_count(), 2);
+        assert_eq!(
+            finish(emitter),
+            "error: a\nerror: aborting due to previous error (error limit reached)\n",
+        );
+    }
+
+    #[test]
+    fn sorted_by_primary_location() {
+        let mut files = SimpleFiles::new();
+        let file_id0 = files.add("b.fun", "one\ntwo\n");
+        let file_id1 = files.add("a.fun", "three\n");
+
+        let writer = termcolor::NoColor::new(Vec::new());
+        let mut emitter = Emitter::new(writer, short_config(), &files).with_sorting(true);
+
+        emitter
+            .emit(Diagnostic::warning().with_message("no location"))
+            .unwrap();
+        emitter
+            .emit(
+                Diagnostic::error()
+                    .with_message("two")
+                    .with_labels(vec![Label::primary(file_id0, 4..7)]),
+            )
+            .unwrap();
+        emitter
+            .emit(
+                Diagnostic::error()
+                    .with_message("one")
+                    .with_labels(vec![Label::primary(file_id0, 0..3)]),
+            )
+            .unwrap();
+        emitter
+            .emit(
+                Diagnostic::error()
+                    .with_message("three")
+                    .with_labels(vec![Label::primary(file_id1, 0..5)]),
+            )
+            .unwrap();
+
+        assert_eq!(
+            finish(emitter),
+            "a.fun:1:1: error: three\n\
+             b.fun:1:1: error: one\n\
+             b.fun:2:1: error: two\n\
+             warning: no location\n\
+             error: aborting due to 3 previous errors; 1 warning emitted\n",
+        );
+    }
+}
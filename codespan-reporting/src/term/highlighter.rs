@@ -0,0 +1,46 @@
+use std::ops::Range;
+use termcolor::ColorSpec;
+
+/// Syntax highlighting for the source code displayed in snippets.
+///
+/// # Example
+///
+/// ```rust
+/// use std::ops::Range;
+/// use codespan_reporting::files::SimpleFile;
+/// use codespan_reporting::term::termcolor::{Color, ColorSpec};
+/// use codespan_reporting::term::Highlighter;
+///
+/// /// Highlights the `let` keyword.
+/// struct Keywords<'a> {
+///     file: &'a SimpleFile<&'a str, &'a str>,
+/// }
+///
+/// impl<'a> Highlighter<()> for Keywords<'a> {
+///     fn highlight(&self, (): (), line_range: Range<usize>) -> Vec<(Range<usize>, ColorSpec)> {
+///         let line = &self.file.source()[line_range.clone()];
+///         let mut style = ColorSpec::new();
+///         style.set_fg(Some(Color::Magenta));
+///
+///         line.match_indices("let ")
+///             .map(|(start, _)| {
+///                 let start = line_range.start + start;
+///                 (start..start + 3, style.clone())
+///             })
+///             .collect()
+///     }
+/// }
+/// ```
+pub trait Highlighter<FileId> {
+    /// Highlight a line of source code, given the range of bytes that it
+    /// covers in a file.
+    ///
+    /// Returns the styles of segments of the line, as ranges of bytes in the
+    /// file. Source code that is not covered by a segment is displayed
+    /// without any styling.
+    fn highlight(
+        &self,
+        file_id: FileId,
+        line_range: Range<usize>,
+    ) -> Vec<(Range<usize>, ColorSpec)>;
+}
@@ -0,0 +1,29 @@
+/// Detect the width of the terminal, in columns.
+///
+/// The `COLUMNS` environment variable takes precedence, falling back to
+/// querying the terminal attached to standard output. Returns `None` if the
+/// width could not be determined, for example when the output is redirected
+/// to a file.
+///
+/// This function requires the `terminal_size` feature to be enabled.
+///
+/// # Example
+///
+/// ```rust
+/// use codespan_reporting::term::{self, Config};
+///
+/// let config = Config {
+///     max_width: term::terminal_width(),
+///     ..Config::default()
+/// };
+/// ```
+pub fn terminal_width() -> Option<usize> {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|columns| *columns > 0)
+        .or_else(|| {
+            let (terminal_size::Width(width), _) = terminal_size::terminal_size()?;
+            Some(usize::from(width)).filter(|width| *width > 0)
+        })
+}
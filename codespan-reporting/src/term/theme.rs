@@ -0,0 +1,639 @@
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use termcolor::{Color, ColorSpec};
+
+use crate::term::{Chars, Config, Styles};
+
+/// A built-in set of styles and characters that themes are based on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serialization", serde(rename_all = "kebab-case"))]
+pub enum Preset {
+    /// The default styles and characters.
+    Default,
+    /// The default styles, drawn using only ASCII characters.
+    Ascii,
+    /// Bold, intense styles that stand out against most backgrounds.
+    HighContrast,
+    /// Styles that avoid distinguishing between red and green.
+    ColorBlind,
+}
+
+impl Preset {
+    /// Allowed values of the preset when parsed from a string.
+    ///
+    /// This is useful for generating documentation via `clap` or `structopt`'s
+    /// `possible_values` configuration.
+    pub const VARIANTS: &'static [&'static str] =
+        &["default", "ascii", "high-contrast", "color-blind"];
+
+    fn name(self) -> &'static str {
+        match self {
+            Preset::Default => "default",
+            Preset::Ascii => "ascii",
+            Preset::HighContrast => "high-contrast",
+            Preset::ColorBlind => "color-blind",
+        }
+    }
+
+    /// The styles of the preset.
+    pub fn styles(self) -> Styles {
+        match self {
+            Preset::Default | Preset::Ascii => Styles::default(),
+            Preset::HighContrast => {
+                let mut styles = Styles::default();
+                for name in Styles::NAMES {
+                    let style = styles.by_name_mut(name).unwrap();
+                    style.set_bold(true).set_intense(true);
+                }
+                let white = ColorSpec::new()
+                    .set_fg(Some(Color::White))
+                    .set_bold(true)
+                    .set_intense(true)
+                    .clone();
+                styles.header_message = white.clone();
+                styles.line_number = white.clone();
+                styles.source_border = white.clone();
+                styles.note_bullet = white;
+                styles
+            }
+            Preset::ColorBlind => {
+                let header = ColorSpec::new().set_bold(true).set_intense(true).clone();
+                let blue = ColorSpec::new().set_fg(Some(Color::Blue)).clone();
+                let cyan = ColorSpec::new().set_fg(Some(Color::Cyan)).clone();
+                let magenta = ColorSpec::new().set_fg(Some(Color::Magenta)).clone();
+                let yellow = ColorSpec::new().set_fg(Some(Color::Yellow)).clone();
+
+                Styles {
+                    header_bug: header.clone().set_fg(Some(Color::Magenta)).clone(),
+                    header_error: header.clone().set_fg(Some(Color::Magenta)).clone(),
+                    header_warning: header.clone().set_fg(Some(Color::Yellow)).clone(),
+                    header_note: header.clone().set_fg(Some(Color::Cyan)).clone(),
+                    header_help: header.clone().set_fg(Some(Color::Blue)).clone(),
+
+                    primary_label_bug: magenta.clone(),
+                    primary_label_error: magenta.clone(),
+                    primary_label_warning: yellow,
+                    primary_label_note: cyan.clone(),
+                    primary_label_help: blue,
+
+                    suggestion_insertion: cyan,
+                    suggestion_removal: magenta,
+
+                    ..Styles::default()
+                }
+            }
+        }
+    }
+
+    /// The characters of the preset.
+    pub fn chars(self) -> Chars {
+        match self {
+            Preset::Default | Preset::HighContrast | Preset::ColorBlind => Chars::default(),
+            Preset::Ascii => Chars {
+                source_border_top_left: '-',
+                source_border_top: '-',
+                source_border_left: '|',
+                source_border_left_break: '.',
+
+                multiline_top_left: '/',
+                multiline_top: '-',
+                multiline_bottom_left: '\\',
+                multiline_bottom: '-',
+                multiline_left: '|',
+
+                pointer_left: '|',
+
+                ..Chars::default()
+            },
+        }
+    }
+}
+
+impl Default for Preset {
+    fn default() -> Preset {
+        Preset::Default
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = &'static str;
+
+    fn from_str(src: &str) -> Result<Preset, &'static str> {
+        match src {
+            _ if src.eq_ignore_ascii_case("default") => Ok(Preset::Default),
+            _ if src.eq_ignore_ascii_case("ascii") => Ok(Preset::Ascii),
+            _ if src.eq_ignore_ascii_case("high-contrast") => Ok(Preset::HighContrast),
+            _ if src.eq_ignore_ascii_case("color-blind") => Ok(Preset::ColorBlind),
+            _ => Err("valid values: default, ascii, high-contrast, color-blind"),
+        }
+    }
+}
+
+/// An error that occurs when loading a theme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ThemeError {
+    /// There is no style or character with the given name.
+    UnknownName(String),
+    /// A style could not be parsed.
+    InvalidStyle(String),
+    /// An entry of a `GCC_COLORS`-style variable could not be parsed.
+    InvalidEntry(String),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::UnknownName(name) => write!(f, "unknown style or character `{}`", name),
+            ThemeError::InvalidStyle(style) => write!(f, "invalid style `{}`", style),
+            ThemeError::InvalidEntry(entry) => write!(f, "invalid entry `{}`", entry),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+/// A description of the styles and characters used to render diagnostics,
+/// which can be loaded without recompiling.
+///
+/// Themes are based on a preset, with individual styles and characters
+/// overridden by the names of the fields of `Styles` and `Chars`. Styles are
+/// written in the same format as in the documentation of those fields, for
+/// example `fg:red bold intense`.
+///
+/// # Example
+///
+/// ```rust
+/// use codespan_reporting::term::{Config, Preset, Theme};
+///
+/// let theme = Theme::new(Preset::Ascii)
+///     .with_style("header_error", "fg:magenta bold")
+///     .with_char("primary_caret", '!');
+///
+/// let mut config = Config::default();
+/// theme.apply(&mut config).unwrap();
+/// assert_eq!(config.chars.source_border_left, '|');
+/// assert_eq!(config.chars.primary_caret, '!');
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Theme {
+    /// The preset that the theme is based on.
+    pub preset: Preset,
+    /// Styles that override the preset, keyed by the names of the fields of
+    /// `Styles`.
+    pub styles: BTreeMap<String, String>,
+    /// Characters that override the preset, keyed by the names of the fields
+    /// of `Chars`.
+    pub chars: BTreeMap<String, char>,
+}
+
+impl Theme {
+    /// Create a new theme based on a preset.
+    pub fn new(preset: Preset) -> Theme {
+        Theme {
+            preset,
+            styles: BTreeMap::new(),
+            chars: BTreeMap::new(),
+        }
+    }
+
+    /// Describe some existing styles and characters as a theme.
+    pub fn from_config(styles: &Styles, chars: &Chars) -> Theme {
+        let mut theme = Theme::new(Preset::Default);
+        for name in Styles::NAMES {
+            let style = styles.by_name(name).unwrap();
+            theme
+                .styles
+                .insert(name.to_string(), Theme::format_style(style));
+        }
+        for name in Chars::NAMES {
+            let ch = chars.by_name(name).unwrap();
+            theme.chars.insert(name.to_string(), *ch);
+        }
+        theme
+    }
+
+    /// Override the style with the given name.
+    pub fn with_style(mut self, name: impl Into<String>, style: impl Into<String>) -> Theme {
+        self.styles.insert(name.into(), style.into());
+        self
+    }
+
+    /// Override the character with the given name.
+    pub fn with_char(mut self, name: impl Into<String>, ch: char) -> Theme {
+        self.chars.insert(name.into(), ch);
+        self
+    }
+
+    /// The styles described by the theme.
+    pub fn styles(&self) -> Result<Styles, ThemeError> {
+        let mut styles = self.preset.styles();
+        for (name, style) in &self.styles {
+            let field = styles
+                .by_name_mut(name)
+                .ok_or_else(|| ThemeError::UnknownName(name.clone()))?;
+            *field = Theme::parse_style(style)?;
+        }
+        Ok(styles)
+    }
+
+    /// The characters described by the theme.
+    pub fn chars(&self) -> Result<Chars, ThemeError> {
+        let mut chars = self.preset.chars();
+        for (name, ch) in &self.chars {
+            let field = chars
+                .by_name_mut(name)
+                .ok_or_else(|| ThemeError::UnknownName(name.clone()))?;
+            *field = *ch;
+        }
+        Ok(chars)
+    }
+
+    /// Replace the styles and characters of a config with the ones described
+    /// by the theme. The config is left unchanged if the theme is invalid.
+    pub fn apply(&self, config: &mut Config) -> Result<(), ThemeError> {
+        let styles = self.styles()?;
+        let chars = self.chars()?;
+        config.styles = styles;
+        config.chars = chars;
+        Ok(())
+    }
+
+    /// Parse a style, for example `fg:red bold intense`.
+    ///
+    /// A style is a list of the following attributes, separated by spaces:
+    ///
+    /// - `fg:<color>`: the foreground color
+    /// - `bg:<color>`: the background color
+    /// - `bold`, `intense`, and `underline`
+    ///
+    /// Colors are either named (`black`, `blue`, `green`, `red`, `cyan`,
+    /// `magenta`, `yellow`, or `white`), an ANSI 256 color number, or a comma
+    /// separated RGB triple, like `255,128,0`.
+    pub fn parse_style(src: &str) -> Result<ColorSpec, ThemeError> {
+        let invalid = || ThemeError::InvalidStyle(src.to_owned());
+        let parse_color = |color: &str| Color::from_str(color).map_err(|_| invalid());
+
+        let mut style = ColorSpec::new();
+        for attribute in src.split_whitespace() {
+            match attribute {
+                "bold" => style.set_bold(true),
+                "intense" => style.set_intense(true),
+                "underline" => style.set_underline(true),
+                _ if attribute.starts_with("fg:") => {
+                    style.set_fg(Some(parse_color(&attribute[3..])?))
+                }
+                _ if attribute.starts_with("bg:") => {
+                    style.set_bg(Some(parse_color(&attribute[3..])?))
+                }
+                _ => return Err(invalid()),
+            };
+        }
+        Ok(style)
+    }
+
+    /// Format a style in the format accepted by `Theme::parse_style`.
+    pub fn format_style(style: &ColorSpec) -> String {
+        fn format_color(color: &Color) -> String {
+            match color {
+                Color::Black => "black".to_owned(),
+                Color::Blue => "blue".to_owned(),
+                Color::Green => "green".to_owned(),
+                Color::Red => "red".to_owned(),
+                Color::Cyan => "cyan".to_owned(),
+                Color::Magenta => "magenta".to_owned(),
+                Color::Yellow => "yellow".to_owned(),
+                Color::White => "white".to_owned(),
+                Color::Ansi256(n) => n.to_string(),
+                Color::Rgb(r, g, b) => format!("{},{},{}", r, g, b),
+                _ => "white".to_owned(),
+            }
+        }
+
+        let mut attributes = Vec::new();
+        if let Some(fg) = style.fg() {
+            attributes.push(format!("fg:{}", format_color(fg)));
+        }
+        if let Some(bg) = style.bg() {
+            attributes.push(format!("bg:{}", format_color(bg)));
+        }
+        if style.bold() {
+            attributes.push("bold".to_owned());
+        }
+        if style.intense() {
+            attributes.push("intense".to_owned());
+        }
+        if style.underline() {
+            attributes.push("underline".to_owned());
+        }
+        attributes.join(" ")
+    }
+
+    /// Parse a theme from a `GCC_COLORS`-style list of entries, for example
+    /// `error=01;35:note_bullet=36:primary_caret=!`.
+    ///
+    /// Entries are separated by colons, and set the style or character with
+    /// the given name. Styles are written as `;` separated SGR parameters, as
+    /// in `GCC_COLORS`.
+    /// The `error`, `warning`, `note`, `fixit-insert`, and `fixit-delete`
+    /// names used by GCC are also supported, and the other names used by GCC
+    /// are ignored, so that `GCC_COLORS` itself can be used. Any other name is
+    /// an error.
+    pub fn from_colors(src: &str) -> Result<Theme, ThemeError> {
+        let mut theme = Theme::default();
+
+        for entry in src.split(':').filter(|entry| !entry.is_empty()) {
+            let invalid = || ThemeError::InvalidEntry(entry.to_owned());
+
+            let mut parts = entry.splitn(2, '=');
+            let name = parts.next().unwrap_or("");
+            let value = parts.next().ok_or_else(invalid)?;
+
+            if Chars::NAMES.contains(&name) {
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => theme.chars.insert(name.to_owned(), ch),
+                    (_, _) => return Err(invalid()),
+                };
+                continue;
+            }
+
+            let names: &[&str] = match name {
+                "error" => &["header_error", "primary_label_error"],
+                "warning" => &["header_warning", "primary_label_warning"],
+                "note" => &["header_note", "primary_label_note"],
+                "fixit-insert" => &["suggestion_insertion"],
+                "fixit-delete" => &["suggestion_removal"],
+                _ if Styles::NAMES.contains(&name) => std::slice::from_ref(&name),
+                _ if GCC_COLORS_NAMES.contains(&name) => continue,
+                _ => return Err(ThemeError::UnknownName(name.to_owned())),
+            };
+            let style = parse_sgr(value).ok_or_else(invalid)?;
+            let style = Theme::format_style(&style);
+            for name in names {
+                theme.styles.insert((*name).to_owned(), style.clone());
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// Load a theme from a `GCC_COLORS`-style environment variable, returning
+    /// `None` if the variable is not set.
+    ///
+    /// See `Theme::from_colors` for the format of the variable.
+    pub fn from_env(name: &str) -> Result<Option<Theme>, ThemeError> {
+        match std::env::var(name) {
+            Ok(colors) => Theme::from_colors(&colors).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// The names used in `GCC_COLORS` that have no equivalent style.
+const GCC_COLORS_NAMES: &[&str] = &[
+    "caret",
+    "diff-delete",
+    "diff-filename",
+    "diff-hunk",
+    "diff-insert",
+    "fnname",
+    "highlight-a",
+    "highlight-b",
+    "invalid",
+    "locus",
+    "path",
+    "quote",
+    "range1",
+    "range2",
+    "targs",
+    "type-diff",
+    "valid",
+];
+
+/// Parse a list of SGR parameters, as used by ANSI escape codes.
+fn parse_sgr(src: &str) -> Option<ColorSpec> {
+    const COLORS: [Color; 8] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+
+    // Extended colors, like `38;5;208` and `38;2;255;128;0`
+    fn extended_color(params: &mut impl Iterator<Item = u8>) -> Option<Color> {
+        match params.next()? {
+            5 => Some(Color::Ansi256(params.next()?)),
+            2 => Some(Color::Rgb(params.next()?, params.next()?, params.next()?)),
+            _ => None,
+        }
+    }
+
+    let mut params = Vec::new();
+    for param in src.split(';') {
+        params.push(match param {
+            "" => 0,
+            _ => param.parse().ok()?,
+        });
+    }
+
+    let mut style = ColorSpec::new();
+    let mut params = params.into_iter();
+    while let Some(param) = params.next() {
+        match param {
+            0 => style.clear(),
+            1 => {
+                style.set_bold(true);
+            }
+            4 => {
+                style.set_underline(true);
+            }
+            22 => {
+                style.set_bold(false);
+            }
+            24 => {
+                style.set_underline(false);
+            }
+            30..=37 => {
+                style.set_fg(Some(COLORS[usize::from(param - 30)]));
+            }
+            38 => {
+                style.set_fg(Some(extended_color(&mut params)?));
+            }
+            39 => {
+                style.set_fg(None);
+            }
+            40..=47 => {
+                style.set_bg(Some(COLORS[usize::from(param - 40)]));
+            }
+            48 => {
+                style.set_bg(Some(extended_color(&mut params)?));
+            }
+            49 => {
+                style.set_bg(None);
+            }
+            90..=97 => {
+                style
+                    .set_fg(Some(COLORS[usize::from(param - 90)]))
+                    .set_intense(true);
+            }
+            100..=107 => {
+                style
+                    .set_bg(Some(COLORS[usize::from(param - 100)]))
+                    .set_intense(true);
+            }
+            _ => return None,
+        }
+    }
+    Some(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_format_styles() {
+        let style = Theme::parse_style("fg:red bg:0,128,255 bold underline").unwrap();
+        assert_eq!(style.fg(), Some(&Color::Red));
+        assert_eq!(style.bg(), Some(&Color::Rgb(0, 128, 255)));
+        assert!(style.bold() && style.underline() && !style.intense());
+        assert_eq!(
+            Theme::format_style(&style),
+            "fg:red bg:0,128,255 bold underline",
+        );
+
+        assert_eq!(Theme::parse_style(""), Ok(ColorSpec::new()));
+        assert_eq!(
+            Theme::parse_style("fg:purple"),
+            Err(ThemeError::InvalidStyle("fg:purple".to_owned())),
+        );
+        assert_eq!(
+            Theme::parse_style("blinking"),
+            Err(ThemeError::InvalidStyle("blinking".to_owned())),
+        );
+    }
+
+    #[test]
+    fn default_styles_round_trip() {
+        let config = Config::default();
+        let theme = Theme::from_config(&config.styles, &config.chars);
+
+        assert_eq!(theme.styles["header_error"], "fg:red bold intense");
+        assert_eq!(theme.chars["source_border_left"], '│');
+
+        let styles = theme.styles().unwrap();
+        for name in Styles::NAMES {
+            assert_eq!(styles.by_name(name), config.styles.by_name(name));
+        }
+    }
+
+    #[test]
+    fn unknown_names() {
+        let theme = Theme::default().with_style("header_fatal", "fg:red");
+        assert_eq!(
+            theme.styles().unwrap_err(),
+            ThemeError::UnknownName("header_fatal".to_owned()),
+        );
+
+        let theme = Theme::default().with_char("caret", '!');
+        assert_eq!(
+            theme.chars().unwrap_err(),
+            ThemeError::UnknownName("caret".to_owned()),
+        );
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!("high-contrast".parse(), Ok(Preset::HighContrast));
+        assert!("neon".parse::<Preset>().is_err());
+
+        for preset in Preset::VARIANTS {
+            let preset = preset.parse::<Preset>().unwrap();
+            assert_eq!(preset.to_string().parse(), Ok(preset));
+        }
+
+        let chars = Preset::Ascii.chars();
+        for name in Chars::NAMES {
+            assert!(chars.by_name(name).unwrap().is_ascii(), "{}", name);
+        }
+
+        let styles = Preset::ColorBlind.styles();
+        for name in Styles::NAMES {
+            let fg = styles.by_name(name).unwrap().fg();
+            assert_ne!(fg, Some(&Color::Red), "{}", name);
+            assert_ne!(fg, Some(&Color::Green), "{}", name);
+        }
+    }
+
+    #[test]
+    fn gcc_colors() {
+        let theme = Theme::from_colors(
+            "error=01;31:warning=01;35:note=01;36:caret=01;32:locus=01:quote=01",
+        )
+        .unwrap();
+        let styles = theme.styles().unwrap();
+
+        assert_eq!(Theme::format_style(&styles.header_error), "fg:red bold");
+        assert_eq!(
+            Theme::format_style(&styles.primary_label_error),
+            "fg:red bold"
+        );
+        assert_eq!(
+            Theme::format_style(&styles.header_warning),
+            "fg:magenta bold"
+        );
+        assert_eq!(Theme::format_style(&styles.header_note), "fg:cyan bold");
+    }
+
+    #[test]
+    fn gcc_colors_with_field_names() {
+        let theme = Theme::from_colors("line_number=38;5;208:secondary_label=34;4:primary_caret=!")
+            .unwrap();
+        let styles = theme.styles().unwrap();
+        let chars = theme.chars().unwrap();
+
+        assert_eq!(styles.line_number.fg(), Some(&Color::Ansi256(208)));
+        assert_eq!(
+            Theme::format_style(&styles.secondary_label),
+            "fg:blue underline"
+        );
+        assert_eq!(chars.primary_caret, '!');
+
+        assert_eq!(
+            Theme::from_colors("line_number"),
+            Err(ThemeError::InvalidEntry("line_number".to_owned())),
+        );
+        assert_eq!(
+            Theme::from_colors("primary_caret=!!"),
+            Err(ThemeError::InvalidEntry("primary_caret=!!".to_owned())),
+        );
+        assert_eq!(
+            Theme::from_colors("error=01;300"),
+            Err(ThemeError::InvalidEntry("error=01;300".to_owned())),
+        );
+    }
+
+    #[test]
+    fn gcc_colors_unknown_name() {
+        assert_eq!(
+            Theme::from_colors("header_eror=01;31"),
+            Err(ThemeError::UnknownName("header_eror".to_owned())),
+        );
+    }
+}
@@ -1,19 +1,27 @@
 mod border;
 mod diagnostic;
+mod explanation;
 mod gutter;
 mod header;
+mod hyperlink;
 mod locus;
 mod new_line;
 mod note;
 mod source_snippet;
+mod suggestion;
 mod underline;
+mod wrap;
 
 pub use self::border::*;
 pub use self::diagnostic::*;
+pub use self::explanation::*;
 pub use self::gutter::*;
 pub use self::header::*;
+pub use self::hyperlink::*;
 pub use self::locus::*;
 pub use self::new_line::*;
 pub use self::note::*;
 pub use self::source_snippet::*;
+pub use self::suggestion::*;
 pub use self::underline::*;
+pub use self::wrap::*;
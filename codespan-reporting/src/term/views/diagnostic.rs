@@ -2,11 +2,15 @@ use std::io;
 use std::ops::Range;
 use termcolor::WriteColor;
 
-use crate::diagnostic::{Diagnostic, LabelStyle};
+use crate::diagnostic::{Diagnostic, Label, LabelStyle, Severity};
 use crate::files::Files;
-use crate::term::Config;
+use crate::term::config::escaped_char_kind;
+use crate::term::{Config, DisplayStyle, EscapeStyle, Highlighter};
 
-use super::{Header, Locus, Note};
+use super::{
+    context_end_line_index, display_columns, severity_name, ExplanationHint, FileMark, Header,
+    Locus, Mark, MarkGroup, MarkStyle, Note,
+};
 
 /// Count the number of decimal digits in `n`.
 fn count_digits(mut n: usize) -> usize {
@@ -25,9 +29,148 @@ fn merge(range0: &Range<usize>, range1: &Range<usize>) -> Range<usize> {
     start..end
 }
 
+/// The additional gutter width used to indent the source snippets of child
+/// diagnostics.
+const CHILD_INDENT: usize = 3;
+
+/// Group the marks of some labels by file, widening the gutter padding to fit
+/// the line numbers of the labelled source code.
+fn group_marks<'a, 'files, F>(
+    files: &'files F,
+    config: &Config,
+    severity: Severity,
+    labels: &'a [Label<F::FileId>],
+    gutter_padding: &mut usize,
+) -> Vec<(F::FileId, MarkGroup<'a, F::Origin>)>
+where
+    F: Files<'files>,
+{
+    let mut mark_groups = Vec::new();
+
+    for label in labels {
+        let mark_style = match label.style {
+            LabelStyle::Primary => MarkStyle::Primary(severity),
+            LabelStyle::Secondary => MarkStyle::Secondary,
+        };
+
+        let mark_group = match mark_groups
+            .iter_mut()
+            .find(|(file_id, _)| label.file_id == *file_id)
+        {
+            Some((_, mark_group)) => mark_group,
+            None => {
+                mark_groups.push((
+                    label.file_id,
+                    MarkGroup {
+                        origin: files.origin(label.file_id).expect("origin"),
+                        range: None,
+                        marks: Vec::new(),
+                        file_marks: Vec::new(),
+                    },
+                ));
+                &mut mark_groups.last_mut().unwrap().1
+            }
+        };
+
+        let range = match label.range.as_ref() {
+            Some(range) => range,
+            None => {
+                // Labels that refer to the whole file are displayed without source code
+                mark_group.file_marks.push(FileMark {
+                    style: mark_style,
+                    message: label.message.as_str(),
+                });
+                continue;
+            }
+        };
+
+        // Compute the width of the gutter for the following source snippets and notes,
+        // including the lines of context shown after the label
+        let end_line_index = files
+            .line_index(label.file_id, range.end)
+            .expect("end_line_index");
+        let end_line_index = context_end_line_index(
+            files,
+            label.file_id,
+            end_line_index,
+            config.after_label_lines,
+        );
+        let end_line = files.line(label.file_id, end_line_index).expect("end_line");
+        *gutter_padding = std::cmp::max(*gutter_padding, count_digits(end_line.number));
+
+        mark_group.range = Some(match &mark_group.range {
+            None => range.clone(),
+            Some(group_range) => merge(group_range, range),
+        });
+        mark_group.marks.push(Mark {
+            style: mark_style,
+            range: range.clone(),
+            message: label.message.as_str(),
+        });
+    }
+
+    // Sort marks lexicographically by the range of source code they cover.
+    for (_, mark_group) in mark_groups.iter_mut() {
+        mark_group.marks.sort_by_key(|mark| {
+            // `Range<usize>` doesn't implement `Ord`, so convert to `(usize, usize)`
+            // to piggyback off its lexicographic sorting implementation.
+            (mark.range.start, mark.range.end)
+        });
+    }
+
+    mark_groups
+}
+
+/// Find the characters in the labelled source code that are escaped when
+/// displayed, in the order that they first appear.
+fn escaped_chars<'a, 'files, F>(
+    files: &'files F,
+    labels: impl IntoIterator<Item = &'a Label<F::FileId>>,
+) -> Vec<char>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    let mut chars = Vec::new();
+
+    for label in labels {
+        let range = match label.range.as_ref() {
+            Some(range) => range,
+            None => continue,
+        };
+        let start_line_index = files
+            .line_index(label.file_id, range.start)
+            .expect("start_line_index");
+        let end_line_index = files
+            .line_index(label.file_id, range.end)
+            .expect("end_line_index");
+
+        for line_index in start_line_index..=end_line_index {
+            let line = files.line(label.file_id, line_index).expect("line");
+            let source = line.source.as_ref();
+            let start = range.start.saturating_sub(line.start);
+            let end = std::cmp::min(range.end - line.start, source.len());
+            let marked_chars = source
+                .get(start..end)
+                .unwrap_or("")
+                .chars()
+                .filter(|ch| escaped_char_kind(*ch).is_some());
+            for ch in marked_chars {
+                if !chars.contains(&ch) {
+                    chars.push(ch);
+                }
+            }
+        }
+    }
+
+    chars
+}
+
 /// Output a richly formatted diagnostic, with source code previews.
 pub struct RichDiagnostic<'a, FileId> {
     diagnostic: &'a Diagnostic<FileId>,
+    highlighter: Option<&'a dyn Highlighter<FileId>>,
+    is_snippets_only: bool,
 }
 
 impl<'a, FileId> RichDiagnostic<'a, FileId>
@@ -35,7 +178,27 @@ where
     FileId: Copy + PartialEq,
 {
     pub fn new(diagnostic: &'a Diagnostic<FileId>) -> RichDiagnostic<'a, FileId> {
-        RichDiagnostic { diagnostic }
+        RichDiagnostic {
+            diagnostic,
+            highlighter: None,
+            is_snippets_only: false,
+        }
+    }
+
+    /// Highlight the source code of snippets using the given highlighter.
+    pub fn with_highlighter(
+        mut self,
+        highlighter: Option<&'a dyn Highlighter<FileId>>,
+    ) -> RichDiagnostic<'a, FileId> {
+        self.highlighter = highlighter;
+        self
+    }
+
+    /// Only emit the source snippets of the labels, without the header,
+    /// notes, child diagnostics, or suggestions.
+    pub fn snippets_only(mut self) -> RichDiagnostic<'a, FileId> {
+        self.is_snippets_only = true;
+        self
     }
 
     pub fn emit<'files>(
@@ -47,62 +210,44 @@ where
     where
         FileId: 'files,
     {
-        use super::{NewLine, SourceSnippet};
+        use super::{Gutter, NewLine, SourceSnippet, SuggestionSnippet};
 
         // Group marks by file
 
-        let mut mark_groups = Vec::new();
         let mut gutter_padding = 0;
+        let mark_groups = group_marks(
+            files,
+            config,
+            self.diagnostic.severity,
+            &self.diagnostic.labels,
+            &mut gutter_padding,
+        );
+        let child_mark_groups = self
+            .diagnostic
+            .children
+            .iter()
+            .map(|child| {
+                group_marks(
+                    files,
+                    config,
+                    child.severity,
+                    &child.labels,
+                    &mut gutter_padding,
+                )
+            })
+            .collect::<Vec<_>>();
 
-        for label in &self.diagnostic.labels {
-            use super::{Mark, MarkGroup, MarkStyle};
-
-            let mark_style = match label.style {
-                LabelStyle::Primary => MarkStyle::Primary(self.diagnostic.severity),
-                LabelStyle::Secondary => MarkStyle::Secondary,
-            };
-
-            // Compute the width of the gutter for the following source snippets and notes
+        // Ensure the gutter is wide enough for the source lines shown by suggestions
+        for suggestion in &self.diagnostic.suggestions {
             let end_line = files
-                .line_index(label.file_id, label.range.end)
-                .and_then(|index| files.line(label.file_id, index))
+                .line_index(suggestion.file_id, suggestion.range.end)
+                .and_then(|index| files.line(suggestion.file_id, index))
                 .expect("end_line");
-            gutter_padding = std::cmp::max(gutter_padding, count_digits(end_line.number));
-
-            let mark = Mark {
-                style: mark_style,
-                range: label.range.clone(),
-                message: label.message.as_str(),
-            };
-
-            // TODO: Group contiguous line index ranges using some sort of interval set algorithm
-            // TODO: Flatten mark groups to overlapping underlines that can be easily rendered.
-            match mark_groups
-                .iter_mut()
-                .find(|(file_id, _)| label.file_id == *file_id)
-            {
-                None => mark_groups.push((
-                    label.file_id,
-                    MarkGroup {
-                        origin: files.origin(label.file_id).expect("origin"),
-                        range: label.range.clone(),
-                        marks: vec![mark],
-                    },
-                )),
-                Some((_, mark_group)) => {
-                    mark_group.range = merge(&mark_group.range, &mark.range);
-                    mark_group.marks.push(mark);
-                }
-            }
-        }
-
-        // Sort marks lexicographically by the range of source code they cover.
-        for (_, mark_group) in mark_groups.iter_mut() {
-            mark_group.marks.sort_by_key(|mark| {
-                // `Range<usize>` doesn't implement `Ord`, so convert to `(usize, usize)`
-                // to piggyback off its lexicographic sorting implementation.
-                (mark.range.start, mark.range.end)
-            });
+            let inserted_lines = suggestion.replacement.matches('\n').count();
+            gutter_padding = std::cmp::max(
+                gutter_padding,
+                count_digits(end_line.number + inserted_lines),
+            );
         }
 
         // Emit the title
@@ -110,9 +255,15 @@ where
         // ```text
         // error[E0001]: unexpected type in `+` application
         // ```
-        Header::new(self.diagnostic).emit(writer, config)?;
-        if !mark_groups.is_empty() {
-            NewLine::new().emit(writer, config)?;
+        // rustc starts the snippets on the line after the header
+        let is_rustc = config.display_style == DisplayStyle::Rustc;
+        if !self.is_snippets_only {
+            Header::from_diagnostic(self.diagnostic)
+                .with_wrapping(0)
+                .emit(writer, config)?;
+            if !mark_groups.is_empty() && !is_rustc {
+                NewLine::new().emit(writer, config)?;
+            }
         }
 
         // Emit the source snippets
@@ -124,21 +275,195 @@ where
         //   │         ^^ expected `Int` but found `String`
         //   │
         // ```
-        for (file_id, mark_group) in mark_groups {
-            SourceSnippet::new(gutter_padding, file_id, mark_group).emit(files, writer, config)?;
+        for (i, (file_id, mark_group)) in mark_groups.into_iter().enumerate() {
+            SourceSnippet::new(gutter_padding, file_id, mark_group)
+                .with_highlighter(self.highlighter)
+                .with_continuation(i > 0)
+                .emit(files, writer, config)?;
+        }
+        if self.is_snippets_only {
+            return Ok(());
         }
 
-        // Additional notes
+        // Additional notes, which rustc introduces with their severity
         //
         // ```text
         // = expected type `Int`
         //      found type `String`
         // ```
+        let note_title = if is_rustc {
+            Some(severity_name(Severity::Note))
+        } else {
+            None
+        };
         for note in &self.diagnostic.notes {
-            Note::new(gutter_padding, &note).emit(writer, config)?;
+            Note::new(gutter_padding, note)
+                .with_title(note_title)
+                .emit(writer, config)?;
+        }
+
+        // Notes about the characters that were escaped in the labelled source
+        //
+        // ```text
+        // = the labelled source code contains the bidirectional formatting character `<U+202E>`
+        // ```
+        if config.escape_style != EscapeStyle::Verbatim {
+            let labels = self.diagnostic.labels.iter().chain(
+                self.diagnostic
+                    .children
+                    .iter()
+                    .flat_map(|child| &child.labels),
+            );
+            for ch in escaped_chars(files, labels) {
+                let note = format!(
+                    "the labelled source code contains the {} character `{}`",
+                    escaped_char_kind(ch).unwrap_or("special"),
+                    config.escape_style.escape(ch).unwrap_or_default(),
+                );
+                Note::new(gutter_padding, &note)
+                    .with_title(note_title)
+                    .emit(writer, config)?;
+            }
+        }
+
+        // rustc shows the child diagnostics and suggestions as part of the
+        // diagnostic, followed by a single blank line
+        if is_rustc {
+            self.emit_rustc_children(files, writer, config, gutter_padding, child_mark_groups)?;
+            NewLine::new().emit(writer, config)?;
+            return self.emit_explanation_hint(writer, config);
         }
         NewLine::new().emit(writer, config)?;
 
+        // Child diagnostics, indented under the parent diagnostic
+        //
+        // ```text
+        //    note: the previous definition is here
+        //
+        //       ┌── test:1:1 ───
+        //       │
+        //     1 │ foo = 1
+        //       │ --- previous definition
+        //       │
+        // ```
+        let child_gutter_padding = gutter_padding + CHILD_INDENT;
+        for (child, mark_groups) in self.diagnostic.children.iter().zip(child_mark_groups) {
+            Gutter::new(None, gutter_padding).emit(writer, config)?;
+            Header::new(child.severity, None, &child.message)
+                .with_wrapping(gutter_padding + 2)
+                .emit(writer, config)?;
+            if !mark_groups.is_empty() {
+                NewLine::new().emit(writer, config)?;
+            }
+            for (i, (file_id, mark_group)) in mark_groups.into_iter().enumerate() {
+                SourceSnippet::new(child_gutter_padding, file_id, mark_group)
+                    .with_highlighter(self.highlighter)
+                    .with_continuation(i > 0)
+                    .emit(files, writer, config)?;
+            }
+            NewLine::new().emit(writer, config)?;
+        }
+
+        // Suggested edits
+        //
+        // ```text
+        // help: pass an `Int` instead
+        //
+        //   ┌── test:2:9 ───
+        //   │
+        // 2 │ (+ test 1)
+        //   │         ~
+        //   │
+        // ```
+        for suggestion in &self.diagnostic.suggestions {
+            Header::new(Severity::Help, None, &suggestion.message)
+                .with_wrapping(0)
+                .emit(writer, config)?;
+            NewLine::new().emit(writer, config)?;
+            SuggestionSnippet::new(gutter_padding, suggestion).emit(files, writer, config)?;
+            NewLine::new().emit(writer, config)?;
+        }
+
+        self.emit_explanation_hint(writer, config)
+    }
+
+    /// Emit the child diagnostics and suggestions in the layout used by
+    /// rustc, which doesn't indent them. Child diagnostics without labels are
+    /// shown as notes.
+    ///
+    /// ```text
+    ///   = help: consider removing the definition
+    /// note: the previous definition is here
+    ///  --> test:1:1
+    ///   |
+    /// 1 | foo = 1
+    ///   | --- previous definition
+    ///   |
+    /// help: pass an `Int` instead
+    ///   |
+    /// 2 | (+ test 1)
+    ///   |         ~
+    ///   |
+    /// ```
+    fn emit_rustc_children<'files, F: Files<'files, FileId = FileId>>(
+        &self,
+        files: &'files F,
+        writer: &mut (impl WriteColor + ?Sized),
+        config: &Config,
+        gutter_padding: usize,
+        child_mark_groups: Vec<Vec<(FileId, MarkGroup<'_, F::Origin>)>>,
+    ) -> io::Result<()>
+    where
+        FileId: 'files,
+    {
+        use super::{SourceSnippet, SuggestionSnippet};
+
+        for (child, mark_groups) in self.diagnostic.children.iter().zip(child_mark_groups) {
+            if mark_groups.is_empty() {
+                Note::new(gutter_padding, &child.message)
+                    .with_title(Some(severity_name(child.severity)))
+                    .emit(writer, config)?;
+                continue;
+            }
+            Header::new(child.severity, None, &child.message)
+                .with_wrapping(0)
+                .emit(writer, config)?;
+            for (i, (file_id, mark_group)) in mark_groups.into_iter().enumerate() {
+                SourceSnippet::new(gutter_padding, file_id, mark_group)
+                    .with_highlighter(self.highlighter)
+                    .with_continuation(i > 0)
+                    .emit(files, writer, config)?;
+            }
+        }
+
+        for suggestion in &self.diagnostic.suggestions {
+            Header::new(Severity::Help, None, &suggestion.message)
+                .with_wrapping(0)
+                .emit(writer, config)?;
+            SuggestionSnippet::new(gutter_padding, suggestion).emit(files, writer, config)?;
+        }
+
+        Ok(())
+    }
+
+    /// Emit a hint pointing to the extended explanation of the diagnostic's
+    /// code.
+    ///
+    /// ```text
+    /// For more information about this error, try `mytool --explain E0001`.
+    /// ```
+    fn emit_explanation_hint(
+        &self,
+        writer: &mut (impl WriteColor + ?Sized),
+        config: &Config,
+    ) -> io::Result<()> {
+        use super::NewLine;
+
+        if let (Some(command), Some(code)) = (&config.explain_command, &self.diagnostic.code) {
+            ExplanationHint::new(self.diagnostic.severity, command, code).emit(writer, config)?;
+            NewLine::new().emit(writer, config)?;
+        }
+
         Ok(())
     }
 }
@@ -165,27 +490,568 @@ where
     where
         FileId: 'files,
     {
-        let mut primary_labels = 0;
+        // Located headers for the diagnostic
+        //
+        // ```text
+        // test:2:9: error[E0001]: unexpected type in `+` application
+        // ```
+        let header = Header::from_diagnostic(self.diagnostic);
+        emit_located_headers(files, writer, config, header, &self.diagnostic.labels)?;
 
-        let labels = self.diagnostic.labels.iter();
-        for label in labels.filter(|label| label.style == LabelStyle::Primary) {
-            primary_labels += 1;
+        // Located headers for the child diagnostics
+        //
+        // ```text
+        // test:1:1: note: the previous definition is here
+        // ```
+        for child in &self.diagnostic.children {
+            let header = Header::new(child.severity, None, &child.message);
+            emit_located_headers(files, writer, config, header, &child.labels)?;
+        }
 
-            let origin = files.origin(label.file_id).expect("origin");
-            let start = label.range.start;
-            let line_index = files.line_index(label.file_id, start).expect("line_index");
-            let line = files.line(label.file_id, line_index).expect("line");
+        Ok(())
+    }
+}
+
+/// Emit a header prefixed by the locus of each primary label.
+fn emit_located_headers<'files, F: Files<'files>>(
+    files: &'files F,
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    header: Header<'_>,
+    labels: &[Label<F::FileId>],
+) -> io::Result<()> {
+    let mut primary_labels = 0;
+
+    for label in labels
+        .iter()
+        .filter(|label| label.style == LabelStyle::Primary)
+    {
+        primary_labels += 1;
+
+        let origin = files.origin(label.file_id).expect("origin");
+        match label.range.as_ref() {
+            Some(range) => {
+                let start = range.start;
+                let line_index = files.line_index(label.file_id, start).expect("line_index");
+                let line = files.line(label.file_id, line_index).expect("line");
+
+                Locus::new(origin, line.number, config.column_number(&line, start))
+                    .emit(writer, config)?;
+            }
+            None => Locus::file(origin).emit(writer, config)?,
+        }
+        write!(writer, ": ")?;
+        header.emit(writer, config)?;
+    }
+
+    // Fallback to printing a non-located header if no primary labels were encountered
+    if primary_labels == 0 {
+        header.emit(writer, config)?;
+    }
+
+    Ok(())
+}
+
+/// Output a diagnostic in the layout used by GCC and Clang, with a located
+/// header for each primary label, followed by its source line and a range
+/// marker.
+pub struct GccDiagnostic<'a, FileId> {
+    diagnostic: &'a Diagnostic<FileId>,
+    highlighter: Option<&'a dyn Highlighter<FileId>>,
+}
+
+impl<'a, FileId> GccDiagnostic<'a, FileId>
+where
+    FileId: Copy + PartialEq,
+{
+    pub fn new(diagnostic: &'a Diagnostic<FileId>) -> GccDiagnostic<'a, FileId> {
+        GccDiagnostic {
+            diagnostic,
+            highlighter: None,
+        }
+    }
+
+    /// Highlight the source code of labelled lines using the given
+    /// highlighter.
+    pub fn with_highlighter(
+        mut self,
+        highlighter: Option<&'a dyn Highlighter<FileId>>,
+    ) -> GccDiagnostic<'a, FileId> {
+        self.highlighter = highlighter;
+        self
+    }
+
+    pub fn emit<'files>(
+        &self,
+        files: &'files impl Files<'files, FileId = FileId>,
+        writer: &mut (impl WriteColor + ?Sized),
+        config: &Config,
+    ) -> io::Result<()>
+    where
+        FileId: 'files,
+    {
+        // Located headers for the diagnostic, followed by their source lines
+        //
+        // ```text
+        // test:2:9: error: unexpected type in `+` application [E0001]
+        //     2 | (+ test "")
+        //       |         ^~ expected `Int` but found `String`
+        // ```
+        let header = Header::from_diagnostic(self.diagnostic).with_trailing_code();
+        emit_gcc_labels(
+            files,
+            writer,
+            config,
+            self.highlighter,
+            header,
+            self.diagnostic.severity,
+            &self.diagnostic.labels,
+        )?;
+
+        // Additional notes, which don't have a location
+        //
+        // ```text
+        // note: expected type `Int`
+        // ```
+        for note in &self.diagnostic.notes {
+            Header::new(Severity::Note, None, note).emit(writer, config)?;
+        }
+
+        // Child diagnostics, in the same layout as the parent diagnostic
+        //
+        // ```text
+        // test:1:1: note: the previous definition is here
+        //     1 | foo = 1
+        //       | ^~~
+        // ```
+        for child in &self.diagnostic.children {
+            let header = Header::new(child.severity, None, &child.message);
+            emit_gcc_labels(
+                files,
+                writer,
+                config,
+                self.highlighter,
+                header,
+                child.severity,
+                &child.labels,
+            )?;
+        }
 
-            Locus::new(origin, line.number, line.column_number(start)).emit(writer, config)?;
+        // Suggested edits, located at the start of the edit
+        //
+        // ```text
+        // test:2:9: help: pass an `Int` instead
+        // ```
+        for suggestion in &self.diagnostic.suggestions {
+            let file_id = suggestion.file_id;
+            let start = suggestion.range.start;
+            let origin = files.origin(file_id).expect("origin");
+            let line_index = files.line_index(file_id, start).expect("line_index");
+            let line = files.line(file_id, line_index).expect("line");
+
+            Locus::new(origin, line.number, config.column_number(&line, start))
+                .emit(writer, config)?;
             write!(writer, ": ")?;
-            Header::new(self.diagnostic).emit(writer, config)?;
+            Header::new(Severity::Help, None, &suggestion.message).emit(writer, config)?;
         }
 
-        // Fallback to printing a non-located header if no primary labels were encountered
-        if primary_labels == 0 {
-            Header::new(self.diagnostic).emit(writer, config)?;
+        Ok(())
+    }
+}
+
+/// Emit a located header and source line for each primary label, followed by
+/// notes for the secondary labels that are on other lines.
+fn emit_gcc_labels<'files, F: Files<'files>>(
+    files: &'files F,
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    highlighter: Option<&dyn Highlighter<F::FileId>>,
+    header: Header<'_>,
+    severity: Severity,
+    labels: &[Label<F::FileId>],
+) -> io::Result<()> {
+    let mark_style = |label: &Label<F::FileId>| match label.style {
+        LabelStyle::Primary => MarkStyle::Primary(severity),
+        LabelStyle::Secondary => MarkStyle::Secondary,
+    };
+
+    // The line of a label, as a file and line index
+    let label_line = |label: &Label<F::FileId>| {
+        let range = label.range.as_ref()?;
+        let line_index = files
+            .line_index(label.file_id, range.start)
+            .expect("line_index");
+        Some((label.file_id, line_index))
+    };
+
+    // GCC pads line numbers to at least four digits
+    let gutter_padding = labels
+        .iter()
+        .filter_map(|label| {
+            let (file_id, line_index) = label_line(label)?;
+            let line = files.line(file_id, line_index).expect("line");
+            Some(count_digits(line.number))
+        })
+        .fold(4, std::cmp::max);
+
+    let (primary_labels, secondary_labels): (Vec<_>, Vec<_>) = labels
+        .iter()
+        .partition(|label| label.style == LabelStyle::Primary);
+
+    // Fallback to printing a non-located header if there are no primary labels
+    if primary_labels.is_empty() {
+        header.emit(writer, config)?;
+    }
+
+    for label in &primary_labels {
+        let origin = files.origin(label.file_id).expect("origin");
+        match label_line(label) {
+            None => {
+                Locus::file(origin).emit(writer, config)?;
+                write!(writer, ": ")?;
+                header.emit(writer, config)?;
+            }
+            Some((file_id, line_index)) => {
+                let line = files.line(file_id, line_index).expect("line");
+                let start = label.range.as_ref().map_or(0, |range| range.start);
+                Locus::new(origin, line.number, config.column_number(&line, start))
+                    .emit(writer, config)?;
+                write!(writer, ": ")?;
+                header.emit(writer, config)?;
+
+                // Every label on the line is marked, with their messages
+                let line_labels = labels
+                    .iter()
+                    .filter(|other| label_line(other) == Some((file_id, line_index)))
+                    .map(|label| (mark_style(label), label, label.message.as_str()))
+                    .collect::<Vec<_>>();
+                emit_gcc_source_line(
+                    files,
+                    writer,
+                    config,
+                    highlighter,
+                    gutter_padding,
+                    (file_id, line_index),
+                    &line_labels,
+                )?;
+            }
+        }
+    }
+
+    // Notes for the secondary labels that aren't shown alongside a primary
+    // label, with the message of the label in the header
+    //
+    // ```text
+    // test:1:1: note: defined here
+    //     1 | foo = 1
+    //       | ~~~
+    // ```
+    let primary_lines = primary_labels
+        .iter()
+        .filter_map(|label| label_line(label))
+        .collect::<Vec<_>>();
+    for label in secondary_labels {
+        let note = Header::new(Severity::Note, None, &label.message);
+        let origin = files.origin(label.file_id).expect("origin");
+        match label_line(label) {
+            None => {
+                Locus::file(origin).emit(writer, config)?;
+                write!(writer, ": ")?;
+                note.emit(writer, config)?;
+            }
+            Some(line) if primary_lines.contains(&line) => {}
+            Some((file_id, line_index)) => {
+                // Labels without messages are shown as source lines that
+                // continue the previous note
+                if !label.message.is_empty() {
+                    let line = files.line(file_id, line_index).expect("line");
+                    let start = label.range.as_ref().map_or(0, |range| range.start);
+                    Locus::new(origin, line.number, config.column_number(&line, start))
+                        .emit(writer, config)?;
+                    write!(writer, ": ")?;
+                    note.emit(writer, config)?;
+                }
+                emit_gcc_source_line(
+                    files,
+                    writer,
+                    config,
+                    highlighter,
+                    gutter_padding,
+                    (file_id, line_index),
+                    &[(mark_style(label), label, "")],
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Emit a line of source code, with range markers for the labels that start
+/// on it.
+///
+/// ```text
+///     2 | (+ test "")
+///       |         ^~ expected `Int` but found `String`
+/// ```
+fn emit_gcc_source_line<'files, F: Files<'files>>(
+    files: &'files F,
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    highlighter: Option<&dyn Highlighter<F::FileId>>,
+    gutter_padding: usize,
+    (file_id, line_index): (F::FileId, usize),
+    labels: &[(MarkStyle, &Label<F::FileId>, &str)],
+) -> io::Result<()> {
+    use super::{emit_source_line, BorderLeft, Gutter, NewLine, SingleLineMark, Underlines};
+
+    let line = files.line(file_id, line_index).expect("line");
+    let source = line.source.as_ref().trim_end();
+
+    Gutter::new(line.number, gutter_padding).emit(writer, config)?;
+    BorderLeft::new().emit(writer, config)?;
+    write!(writer, " ")?;
+    emit_source_line(writer, config, highlighter, file_id, line.start, source)?;
+    NewLine::new().emit(writer, config)?;
+
+    // Labels that continue past the end of the line are marked up to the end
+    // of its source code
+    let mut marks = labels
+        .iter()
+        .filter_map(|(style, label, message)| {
+            let range = label.range.as_ref()?;
+            let start = std::cmp::min(range.start - line.start, source.len());
+            let end = std::cmp::min(range.end.saturating_sub(line.start), source.len());
+            let columns = display_columns(config, source, start..end);
+
+            Some(SingleLineMark {
+                style: *style,
+                start: columns.start,
+                end: std::cmp::max(columns.end, columns.start + 1),
+                message,
+            })
+        })
+        .collect::<Vec<_>>();
+    marks.sort_by_key(|mark| mark.start);
+
+    Underlines::new(gutter_padding, &[], &marks)
+        .with_caret_ranges()
+        .emit(writer, config)
+}
+
+/// Output a diagnostic as a list of locations that can be parsed by the
+/// quickfix list of Vim or the compilation mode of Emacs, with a line for
+/// each label, and continuation lines for notes.
+pub struct QuickfixDiagnostic<'a, FileId> {
+    diagnostic: &'a Diagnostic<FileId>,
+}
+
+impl<'a, FileId> QuickfixDiagnostic<'a, FileId>
+where
+    FileId: Copy + PartialEq,
+{
+    pub fn new(diagnostic: &'a Diagnostic<FileId>) -> QuickfixDiagnostic<'a, FileId> {
+        QuickfixDiagnostic { diagnostic }
+    }
+
+    pub fn emit<'files>(
+        &self,
+        files: &'files impl Files<'files, FileId = FileId>,
+        writer: &mut (impl WriteColor + ?Sized),
+        config: &Config,
+    ) -> io::Result<()>
+    where
+        FileId: 'files,
+    {
+        // Located headers for the labels of the diagnostic
+        //
+        // ```text
+        // test:2:9: error[E0001]: unexpected type in `+` application
+        //     = expected `Int` but found `String`
+        // ```
+        let header = Header::from_diagnostic(self.diagnostic);
+        let locus = self.locus();
+        emit_quickfix_labels(
+            files,
+            writer,
+            config,
+            header,
+            locus,
+            &self.diagnostic.labels,
+        )?;
+
+        // Additional notes, continuing the last location
+        //
+        // ```text
+        //     = expected type `Int`
+        //          found type `String`
+        // ```
+        for note in &self.diagnostic.notes {
+            Note::new(QUICKFIX_INDENT, note).emit(writer, config)?;
+        }
+
+        // Located headers for the secondary labels
+        //
+        // ```text
+        // test:1:1: note: the definition of `+`
+        // ```
+        for label in self
+            .diagnostic
+            .labels
+            .iter()
+            .filter(|label| label.style == LabelStyle::Secondary)
+        {
+            let message = match label.message.as_str() {
+                "" => &self.diagnostic.message,
+                message => message,
+            };
+            emit_quickfix_locus(files, writer, config, label.file_id, label.range.as_ref())?;
+            write!(writer, ": ")?;
+            Header::new(Severity::Note, None, message).emit(writer, config)?;
+        }
+
+        // Child diagnostics, located at each of their labels, or at the
+        // location of the diagnostic if they have no labels
+        //
+        // ```text
+        // test:1:1: note: the previous definition is here
+        // test:2:9: help: consider converting the `String`
+        // ```
+        for child in &self.diagnostic.children {
+            if child.labels.is_empty() {
+                match locus {
+                    Some((file_id, range)) => {
+                        let header = Header::new(child.severity, None, &child.message);
+                        emit_quickfix_locus(files, writer, config, file_id, range)?;
+                        write!(writer, ": ")?;
+                        header.emit(writer, config)?;
+                    }
+                    None => {
+                        let note = format!("{}: {}", severity_name(child.severity), child.message);
+                        Note::new(QUICKFIX_INDENT, &note).emit(writer, config)?;
+                    }
+                }
+            }
+            for label in &child.labels {
+                let header = Header::new(child.severity, None, &child.message);
+                emit_quickfix_locus(files, writer, config, label.file_id, label.range.as_ref())?;
+                write!(writer, ": ")?;
+                header.emit(writer, config)?;
+                if !label.message.is_empty() {
+                    Note::new(QUICKFIX_INDENT, &label.message).emit(writer, config)?;
+                }
+            }
+        }
+
+        // Suggested edits, located at the range that they replace
+        //
+        // ```text
+        // test:2:9: help: pass an `Int` instead
+        // ```
+        for suggestion in &self.diagnostic.suggestions {
+            let range = Some(&suggestion.range);
+            emit_quickfix_locus(files, writer, config, suggestion.file_id, range)?;
+            write!(writer, ": ")?;
+            Header::new(Severity::Help, None, &suggestion.message).emit(writer, config)?;
         }
 
         Ok(())
     }
+
+    /// The location of the diagnostic when it has no primary labels: its first
+    /// label, or else the first label of its children, or else its first
+    /// suggestion.
+    fn locus(&self) -> Option<(FileId, Option<&'a Range<usize>>)> {
+        let diagnostic = self.diagnostic;
+        let label = diagnostic.main_label().or_else(|| {
+            diagnostic
+                .children
+                .iter()
+                .find_map(|child| child.labels.first())
+        });
+        match label {
+            Some(label) => Some((label.file_id, label.range.as_ref())),
+            None => diagnostic
+                .suggestions
+                .first()
+                .map(|suggestion| (suggestion.file_id, Some(&suggestion.range))),
+        }
+    }
+}
+
+/// The indentation of the continuation lines of quickfix diagnostics.
+const QUICKFIX_INDENT: usize = 2;
+
+/// Emit a header located at each primary label, followed by the message of
+/// the label. If there are no primary labels, the header is emitted at the
+/// fallback locus, or without a locus if there is none.
+fn emit_quickfix_labels<'files, F: Files<'files>>(
+    files: &'files F,
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    header: Header<'_>,
+    fallback_locus: Option<(F::FileId, Option<&Range<usize>>)>,
+    labels: &[Label<F::FileId>],
+) -> io::Result<()> {
+    let primary_labels = labels
+        .iter()
+        .filter(|label| label.style == LabelStyle::Primary)
+        .collect::<Vec<_>>();
+
+    if primary_labels.is_empty() {
+        if let Some((file_id, range)) = fallback_locus {
+            emit_quickfix_locus(files, writer, config, file_id, range)?;
+            write!(writer, ": ")?;
+        }
+        header.emit(writer, config)?;
+    }
+    for label in primary_labels {
+        emit_quickfix_locus(files, writer, config, label.file_id, label.range.as_ref())?;
+        write!(writer, ": ")?;
+        header.emit(writer, config)?;
+        if !label.message.is_empty() {
+            Note::new(QUICKFIX_INDENT, &label.message).emit(writer, config)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Emit the locus of a range, including its end position for Emacs, which
+/// understands the range format of the GNU coding standards.
+///
+/// ```text
+/// test:2:9
+/// test:2.9-10
+/// ```
+fn emit_quickfix_locus<'files, F: Files<'files>>(
+    files: &'files F,
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    file_id: F::FileId,
+    range: Option<&Range<usize>>,
+) -> io::Result<()> {
+    let origin = files.origin(file_id).expect("origin");
+    let range = match range {
+        Some(range) => range,
+        None => return Locus::file(origin).emit(writer, config),
+    };
+    let position = |byte_index| {
+        let line_index = files.line_index(file_id, byte_index).expect("line_index");
+        let line = files.line(file_id, line_index).expect("line");
+        (line.number, config.column_number(&line, byte_index))
+    };
+
+    let (line_number, column_number) = position(range.start);
+    let locus = Locus::new(origin, line_number, column_number);
+    if config.display_style == DisplayStyle::Emacs && range.end > range.start {
+        // The end of the range is inclusive, so it is the position of the
+        // last character in the range
+        let (end_line_number, end_column_number) = position(range.end - 1);
+        locus
+            .with_end(end_line_number, end_column_number)
+            .emit(writer, config)
+    } else {
+        locus.emit(writer, config)
+    }
 }
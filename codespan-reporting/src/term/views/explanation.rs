@@ -0,0 +1,123 @@
+use std::io;
+use termcolor::WriteColor;
+
+use crate::diagnostic::Severity;
+use crate::term::Config;
+
+use super::{severity_name, NewLine};
+
+/// An extended explanation of a diagnostic code.
+///
+/// ```text
+/// # E0001: unexpected type
+///
+/// An unexpected type was found, for example:
+///
+///     _ = 123 + "hello"
+/// ```
+pub struct Explanation<'a> {
+    text: &'a str,
+}
+
+impl<'a> Explanation<'a> {
+    pub fn new(text: &'a str) -> Explanation<'a> {
+        Explanation { text }
+    }
+
+    pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
+        use std::io::Write;
+
+        let mut in_code_block = false;
+
+        for line in self.text.lines() {
+            let trimmed = line.trim_start();
+
+            if trimmed.starts_with("```") {
+                // Code fences are omitted, only toggling the code block style
+                in_code_block = !in_code_block;
+            } else if in_code_block {
+                // Code blocks are indented, and styled as code
+                //
+                // ```text
+                //     _ = 123 + "hello"
+                // ```
+                write!(writer, "    ")?;
+                writer.set_color(&config.styles.explanation_code)?;
+                write!(config.source(writer), "{}", line.trim_end())?;
+                writer.reset()?;
+                NewLine::new().emit(writer, config)?;
+            } else if trimmed.starts_with('#') {
+                // Headings
+                //
+                // ```text
+                // # E0001: unexpected type
+                // ```
+                writer.set_color(&config.styles.explanation_heading)?;
+                write!(writer, "{}", trimmed.trim_end())?;
+                writer.reset()?;
+                NewLine::new().emit(writer, config)?;
+            } else {
+                // Paragraph text, with inline code spans
+                //
+                // ```text
+                // An unexpected type was found in `+` application.
+                // ```
+                for (i, segment) in line.trim_end().split('`').enumerate() {
+                    match i {
+                        0 => write!(writer, "{}", segment)?,
+                        // Segments at odd indices lie between a pair of backticks
+                        _ if i % 2 == 1 => {
+                            writer.set_color(&config.styles.explanation_code)?;
+                            write!(writer, "`{}", segment)?;
+                        }
+                        _ => {
+                            write!(writer, "`")?;
+                            writer.reset()?;
+                            write!(writer, "{}", segment)?;
+                        }
+                    }
+                }
+                writer.reset()?;
+                NewLine::new().emit(writer, config)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A hint pointing to the extended explanation of a diagnostic code.
+///
+/// ```text
+/// For more information about this error, try `mytool --explain E0001`.
+/// ```
+pub struct ExplanationHint<'a> {
+    severity: Severity,
+    command: &'a str,
+    code: &'a str,
+}
+
+impl<'a> ExplanationHint<'a> {
+    pub fn new(severity: Severity, command: &'a str, code: &'a str) -> ExplanationHint<'a> {
+        ExplanationHint {
+            severity,
+            command,
+            code,
+        }
+    }
+
+    pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
+        writer.set_color(&config.styles.header_message)?;
+        write!(
+            writer,
+            "For more information about this {}, try `{} {}`.",
+            severity_name(self.severity),
+            self.command,
+            self.code,
+        )?;
+        writer.reset()?;
+        NewLine::new().emit(writer, config)?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use std::io;
 use termcolor::WriteColor;
 
-use crate::term::Config;
+use crate::term::{Config, DisplayStyle};
 
 /// The left-hand gutter of a source line.
 pub struct Gutter {
@@ -18,7 +18,11 @@ impl Gutter {
     }
 
     pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
-        write!(writer, " ")?;
+        match config.display_style {
+            // rustc starts its line numbers at the edge of the terminal
+            DisplayStyle::Rustc => {}
+            _ => write!(writer, " ")?,
+        }
         match self.line_number {
             None => {
                 write!(
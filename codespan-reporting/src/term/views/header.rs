@@ -4,7 +4,18 @@ use termcolor::WriteColor;
 use crate::diagnostic::{Diagnostic, Severity};
 use crate::term::Config;
 
-use super::NewLine;
+use super::{wrap_words, HyperlinkEnd, HyperlinkStart, NewLine};
+
+/// The name of a severity, as displayed in headers.
+pub fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug => "bug",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Help => "help",
+        Severity::Note => "note",
+    }
+}
 
 /// Diagnostic header.
 ///
@@ -16,25 +27,53 @@ pub struct Header<'a> {
     severity: Severity,
     code: Option<&'a str>,
     message: &'a str,
+    /// The column that the header starts at, if the message should be wrapped
+    /// to fit within `Config::max_width`.
+    wrap_column: Option<usize>,
+    /// Whether the code follows the message, like `error: message [E0001]`.
+    is_code_trailing: bool,
 }
 
 impl<'a> Header<'a> {
-    pub fn new<FileId>(diagnostic: &'a Diagnostic<FileId>) -> Header<'a> {
+    pub fn new(severity: Severity, code: Option<&'a str>, message: &'a str) -> Header<'a> {
         Header {
-            severity: diagnostic.severity,
-            code: diagnostic.code.as_ref().map(String::as_str),
-            message: &diagnostic.message,
+            severity,
+            code,
+            message,
+            wrap_column: None,
+            is_code_trailing: false,
         }
     }
 
-    fn severity_name(&self) -> &'static str {
-        match self.severity {
-            Severity::Bug => "bug",
-            Severity::Error => "error",
-            Severity::Warning => "warning",
-            Severity::Help => "help",
-            Severity::Note => "note",
-        }
+    /// Write the code after the message, in the way that GCC writes the
+    /// options that enabled a warning.
+    ///
+    /// ```text
+    /// error: unexpected type in `+` application [E0001]
+    /// ```
+    pub fn with_trailing_code(mut self) -> Header<'a> {
+        self.is_code_trailing = true;
+        self
+    }
+
+    /// Wrap long messages at word boundaries, aligning the wrapped lines with
+    /// the start of the message.
+    ///
+    /// ```text
+    /// error[E0001]: unexpected type in `+` application, where a much longer
+    ///               message would continue on the following line
+    /// ```
+    pub fn with_wrapping(mut self, column: usize) -> Header<'a> {
+        self.wrap_column = Some(column);
+        self
+    }
+
+    pub fn from_diagnostic<FileId>(diagnostic: &'a Diagnostic<FileId>) -> Header<'a> {
+        Header::new(
+            diagnostic.severity,
+            diagnostic.code.as_ref().map(String::as_str),
+            &diagnostic.message,
+        )
     }
 
     pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
@@ -44,14 +83,15 @@ impl<'a> Header<'a> {
         // error
         // ```
         writer.set_color(config.styles.header(self.severity))?;
-        write!(writer, "{}", self.severity_name())?;
-        if let Some(code) = &self.code {
+        write!(writer, "{}", severity_name(self.severity))?;
+        let code = self.code.filter(|_| !self.is_code_trailing);
+        if let Some(code) = code {
             // Write error code
             //
             // ```text
             // [E0001]
             // ```
-            write!(writer, "[{}]", code)?;
+            self.emit_code(writer, config, code)?;
         }
 
         // Write diagnostic message
@@ -60,11 +100,71 @@ impl<'a> Header<'a> {
         // : unexpected type in `+` application
         // ```
         writer.set_color(&config.styles.header_message)?;
-        write!(writer, ": {}", self.message)?;
+        match (config.max_width, self.wrap_column) {
+            (Some(max_width), Some(column)) => {
+                let message_column = column
+                    + severity_name(self.severity).len()
+                    + code.map_or(0, |code| config.width(code) + 2)
+                    + 2;
+                let width = max_width.saturating_sub(message_column);
+                let lines = self
+                    .message
+                    .split('\n')
+                    .flat_map(|line| wrap_words(config, line, width));
+                for (i, line) in lines.enumerate() {
+                    match i {
+                        0 => write!(writer, ": {}", line)?,
+                        _ => {
+                            NewLine::new().emit(writer, config)?;
+                            write!(
+                                writer,
+                                "{space: >width$}",
+                                space = "",
+                                width = message_column
+                            )?;
+                            write!(writer, "{}", line)?;
+                        }
+                    }
+                }
+            }
+            (_, _) => write!(writer, ": {}", self.message)?,
+        }
+        if let (true, Some(code)) = (self.is_code_trailing, self.code) {
+            // Write trailing error code
+            //
+            // ```text
+            //  [E0001]
+            // ```
+            write!(writer, " ")?;
+            self.emit_code(writer, config, code)?;
+        }
         writer.reset()?;
 
         NewLine::new().emit(writer, config)?;
 
         Ok(())
     }
+
+    /// Write the code, linked to its documentation when hyperlinks are
+    /// enabled.
+    fn emit_code(
+        &self,
+        writer: &mut (impl WriteColor + ?Sized),
+        config: &Config,
+        code: &str,
+    ) -> io::Result<()> {
+        let url = config
+            .hyperlinks
+            .as_ref()
+            .filter(|_| writer.supports_hyperlinks())
+            .and_then(|hyperlinks| hyperlinks.code_url(code));
+        match &url {
+            Some(url) => {
+                HyperlinkStart::new(url).emit(writer, config)?;
+                write!(writer, "[{}]", code)?;
+                HyperlinkEnd::new().emit(writer, config)
+            }
+            None => write!(writer, "[{}]", code),
+        }
+    }
 }
@@ -0,0 +1,49 @@
+use std::io;
+use termcolor::WriteColor;
+
+use crate::term::Config;
+
+/// The start of an OSC 8 hyperlink, linking the text that is written before
+/// the following `HyperlinkEnd`.
+///
+/// ```text
+/// \x1b]8;;file:///home/user/test\x1b\\
+/// ```
+pub struct HyperlinkStart<'a> {
+    url: &'a str,
+}
+
+impl<'a> HyperlinkStart<'a> {
+    pub fn new(url: &'a str) -> HyperlinkStart<'a> {
+        HyperlinkStart { url }
+    }
+
+    pub fn emit(
+        &self,
+        writer: &mut (impl WriteColor + ?Sized),
+        _config: &Config,
+    ) -> io::Result<()> {
+        write!(writer, "\x1b]8;;{}\x1b\\", self.url)
+    }
+}
+
+/// The end of an OSC 8 hyperlink.
+///
+/// ```text
+/// \x1b]8;;\x1b\\
+/// ```
+pub struct HyperlinkEnd {}
+
+impl HyperlinkEnd {
+    pub fn new() -> HyperlinkEnd {
+        HyperlinkEnd {}
+    }
+
+    pub fn emit(
+        &self,
+        writer: &mut (impl WriteColor + ?Sized),
+        _config: &Config,
+    ) -> io::Result<()> {
+        write!(writer, "\x1b]8;;\x1b\\")
+    }
+}
@@ -1,7 +1,9 @@
 use std::io;
 use termcolor::WriteColor;
 
-use crate::term::Config;
+use crate::term::{Config, DisplayStyle};
+
+use super::{BorderTop, BorderTopLeft, Gutter, HyperlinkEnd, HyperlinkStart, NewLine};
 
 /// The 'location focus' of a source code snippet.
 ///
@@ -11,10 +13,23 @@ use crate::term::Config;
 /// ```text
 /// test:2:9
 /// ```
+///
+/// The position is omitted for loci that refer to a whole file:
+///
+/// ```text
+/// test
+/// ```
+///
+/// Loci with an end position use the range format of the GNU coding
+/// standards, omitting the end line when it is the same as the start line:
+///
+/// ```text
+/// test:2.9-10
+/// ```
 pub struct Locus<Origin> {
     origin: Origin,
-    line_number: usize,
-    column_number: usize,
+    position: Option<(usize, usize)>,
+    end_position: Option<(usize, usize)>,
 }
 
 impl<Origin> Locus<Origin>
@@ -24,22 +39,132 @@ where
     pub fn new(origin: Origin, line_number: usize, column_number: usize) -> Locus<Origin> {
         Locus {
             origin,
-            line_number,
-            column_number,
+            position: Some((line_number, column_number)),
+            end_position: None,
+        }
+    }
+
+    pub fn file(origin: Origin) -> Locus<Origin> {
+        Locus {
+            origin,
+            position: None,
+            end_position: None,
+        }
+    }
+
+    /// Include the inclusive end position of the range that the locus refers
+    /// to.
+    pub fn with_end(mut self, line_number: usize, column_number: usize) -> Locus<Origin> {
+        self.end_position = Some((line_number, column_number));
+        self
+    }
+
+    pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
+        let url = config
+            .hyperlinks
+            .as_ref()
+            .filter(|_| writer.supports_hyperlinks())
+            .and_then(|hyperlinks| hyperlinks.locus_url(&self.origin.to_string(), self.position));
+        if let Some(url) = &url {
+            HyperlinkStart::new(url).emit(writer, config)?;
+        }
+
+        match (self.position, self.end_position) {
+            (Some((line_number, column_number)), Some((end_line_number, end_column_number))) => {
+                write!(
+                    writer,
+                    "{origin}:{line_number}.{column_number}-",
+                    origin = self.origin,
+                    line_number = line_number,
+                    column_number = column_number,
+                )?;
+                if end_line_number != line_number {
+                    write!(writer, "{}.", end_line_number)?;
+                }
+                write!(writer, "{}", end_column_number)?;
+            }
+            (Some((line_number, column_number)), None) => write!(
+                writer,
+                "{origin}:{line_number}:{column_number}",
+                origin = self.origin,
+                line_number = line_number,
+                column_number = column_number,
+            )?,
+            (None, _) => write!(writer, "{origin}", origin = self.origin)?,
+        }
+
+        if url.is_some() {
+            HyperlinkEnd::new().emit(writer, config)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The top border of a snippet, containing the locus of its source code.
+///
+/// ```text
+/// ┌── test:2:9 ───
+/// ```
+///
+/// With `DisplayStyle::Rustc` the locus follows an arrow, or `:::` for
+/// snippets that continue a diagnostic in another file:
+///
+/// ```text
+///  --> test:2:9
+/// ```
+pub struct SnippetTop<Origin> {
+    gutter_padding: usize,
+    locus: Locus<Origin>,
+    is_continuation: bool,
+}
+
+impl<Origin> SnippetTop<Origin>
+where
+    Origin: std::fmt::Display,
+{
+    pub fn new(gutter_padding: usize, locus: Locus<Origin>) -> SnippetTop<Origin> {
+        SnippetTop {
+            gutter_padding,
+            locus,
+            is_continuation: false,
         }
     }
 
-    pub fn emit(
-        &self,
-        writer: &mut (impl WriteColor + ?Sized),
-        _config: &Config,
-    ) -> io::Result<()> {
-        write!(
-            writer,
-            "{origin}:{line_number}:{column_number}",
-            origin = self.origin,
-            line_number = self.line_number,
-            column_number = self.column_number,
-        )
+    /// Mark the snippet as following another snippet of the same diagnostic.
+    pub fn with_continuation(mut self, is_continuation: bool) -> SnippetTop<Origin> {
+        self.is_continuation = is_continuation;
+        self
+    }
+
+    pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
+        match config.display_style {
+            DisplayStyle::Rustc => {
+                let arrow = if self.is_continuation { ":::" } else { "-->" };
+                write!(
+                    writer,
+                    "{space: >width$}",
+                    space = "",
+                    width = self.gutter_padding,
+                )?;
+                writer.set_color(&config.styles.source_border)?;
+                write!(writer, "{}", arrow)?;
+                writer.reset()?;
+                write!(writer, " ")?;
+                self.locus.emit(writer, config)?;
+            }
+            _ => {
+                Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+                BorderTopLeft::new().emit(writer, config)?;
+                BorderTop::new(2).emit(writer, config)?;
+                write!(writer, " ")?;
+                self.locus.emit(writer, config)?;
+                write!(writer, " ")?;
+                BorderTop::new(3).emit(writer, config)?;
+            }
+        }
+        NewLine::new().emit(writer, config)?;
+
+        Ok(())
     }
 }
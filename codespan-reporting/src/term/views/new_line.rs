@@ -16,6 +16,6 @@ impl NewLine {
         writer: &mut (impl WriteColor + ?Sized),
         _config: &Config,
     ) -> io::Result<()> {
-        write!(writer, "\n")
+        writeln!(writer)
     }
 }
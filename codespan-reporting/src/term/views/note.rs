@@ -3,7 +3,7 @@ use termcolor::WriteColor;
 
 use crate::term::Config;
 
-use super::{Gutter, NewLine};
+use super::{wrap_words, Gutter, NewLine};
 
 /// Additional note
 ///
@@ -13,6 +13,7 @@ use super::{Gutter, NewLine};
 /// ```
 pub struct Note<'a> {
     gutter_padding: usize,
+    title: Option<&'a str>,
     message: &'a str,
 }
 
@@ -20,19 +21,52 @@ impl<'a> Note<'a> {
     pub fn new(gutter_padding: usize, message: &'a str) -> Note<'a> {
         Note {
             gutter_padding,
+            title: None,
             message,
         }
     }
 
+    /// Introduce the message with a title, aligning the following lines of
+    /// the message with the first one.
+    ///
+    /// ```text
+    /// = note: expected type `Int`
+    ///            found type `String`
+    /// ```
+    pub fn with_title(mut self, title: Option<&'a str>) -> Note<'a> {
+        self.title = title;
+        self
+    }
+
     pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
-        for (i, line) in self.message.lines().enumerate() {
+        let title = match self.title {
+            Some(title) => format!("{}: ", title),
+            None => String::new(),
+        };
+
+        // Wrap long lines to fit after the gutter, bullet, and title
+        let lines = self
+            .message
+            .lines()
+            .flat_map(|line| match config.max_width {
+                Some(max_width) => {
+                    let width = max_width.saturating_sub(self.gutter_padding + 4 + title.len());
+                    wrap_words(config, line, width)
+                }
+                None => vec![line],
+            });
+
+        for (i, line) in lines.enumerate() {
             Gutter::new(None, self.gutter_padding).emit(writer, config)?;
             match i {
-                0 => Bullet::new().emit(writer, config)?,
-                _ => write!(writer, " ")?,
+                0 => {
+                    Bullet::new().emit(writer, config)?;
+                    write!(writer, " {}", title)?;
+                }
+                _ => write!(writer, "  {: >width$}", "", width = title.len())?,
             }
             // Write line of message
-            write!(writer, " {}", line)?;
+            write!(writer, "{}", line)?;
             NewLine::new().emit(writer, config)?;
         }
 
@@ -1,19 +1,22 @@
 use std::io;
 use std::ops::Range;
-use termcolor::WriteColor;
+use termcolor::{ColorSpec, WriteColor};
 
 use crate::files::Files;
-use crate::term::Config;
+use crate::term::{Config, Highlighter};
 
 use super::{
-    BorderLeft, BorderLeftBreak, BorderTop, BorderTopLeft, Gutter, Locus, MarkStyle, NewLine,
-    Underline, UnderlineBottom, UnderlineLeft, UnderlineTop, UnderlineTopLeft,
+    display_columns, BorderLeft, BorderLeftBreak, ColumnMark, Gutter, Locus, MarkStyle,
+    MultiLineColumns, NewLine, SingleLineMark, SnippetTop, UnderlineBottom, UnderlineTop,
+    Underlines,
 };
 
 pub struct MarkGroup<'a, Origin> {
     pub origin: Origin,
-    pub range: Range<usize>,
+    /// The range covered by the marks, or `None` if there are only file marks.
+    pub range: Option<Range<usize>>,
     pub marks: Vec<Mark<'a>>,
+    pub file_marks: Vec<FileMark<'a>>,
 }
 
 pub struct Mark<'a> {
@@ -22,6 +25,295 @@ pub struct Mark<'a> {
     pub message: &'a str,
 }
 
+/// A mark that spans multiple source lines, along with the column of the
+/// gutter that its left side is drawn in.
+struct MultiLineMark<'m, 'a> {
+    column: usize,
+    start_line_index: usize,
+    end_line_index: usize,
+    mark: &'m Mark<'a>,
+}
+
+/// Marks that are displayed together in a source snippet, covering a
+/// contiguous range of source lines.
+///
+/// Multi-line marks that overlap are displayed in the same unit, each in its
+/// own column, along with the single-line marks on the lines that they cover.
+/// Units are then merged with their neighbours when the lines of context
+/// around them overlap.
+struct MarkUnit<'m, 'a> {
+    start_line_index: usize,
+    end_line_index: usize,
+    /// Single-line marks, along with the index of their source line.
+    single_line_marks: Vec<(usize, &'m Mark<'a>)>,
+    multi_line_marks: Vec<MultiLineMark<'m, 'a>>,
+    /// The number of columns used by the multi-line marks.
+    columns: usize,
+}
+
+impl<'m, 'a> MarkUnit<'m, 'a> {
+    fn new(start_line_index: usize, end_line_index: usize) -> MarkUnit<'m, 'a> {
+        MarkUnit {
+            start_line_index,
+            end_line_index,
+            single_line_marks: Vec::new(),
+            multi_line_marks: Vec::new(),
+            columns: 0,
+        }
+    }
+
+    fn contains_line(&self, line_index: usize) -> bool {
+        self.start_line_index <= line_index && line_index <= self.end_line_index
+    }
+
+    /// Add a multi-line mark to the unit, reusing the first column that is
+    /// free by the time the mark starts.
+    fn push_multi_line(
+        &mut self,
+        start_line_index: usize,
+        end_line_index: usize,
+        mark: &'m Mark<'a>,
+    ) {
+        let multi_line_marks = &self.multi_line_marks;
+        let column = (0..self.columns)
+            .find(|column| {
+                multi_line_marks
+                    .iter()
+                    .filter(|multi_line_mark| multi_line_mark.column == *column)
+                    .all(|multi_line_mark| multi_line_mark.end_line_index < start_line_index)
+            })
+            .unwrap_or(self.columns);
+
+        self.columns = std::cmp::max(self.columns, column + 1);
+        self.end_line_index = std::cmp::max(self.end_line_index, end_line_index);
+        self.multi_line_marks.push(MultiLineMark {
+            column,
+            start_line_index,
+            end_line_index,
+            mark,
+        });
+    }
+
+    /// Merge a unit that comes after this one. The units must not share any
+    /// lines, so the columns of their multi-line marks can be reused.
+    fn merge(&mut self, other: MarkUnit<'m, 'a>) {
+        self.end_line_index = std::cmp::max(self.end_line_index, other.end_line_index);
+        self.single_line_marks.extend(other.single_line_marks);
+        self.multi_line_marks.extend(other.multi_line_marks);
+        self.columns = std::cmp::max(self.columns, other.columns);
+    }
+
+    /// Whether a line has a label on it, either from a single-line mark or
+    /// from the start or end of a multi-line mark.
+    fn is_labelled(&self, line_index: usize) -> bool {
+        self.single_line_marks
+            .iter()
+            .any(|(mark_line_index, _)| *mark_line_index == line_index)
+            || self.multi_line_marks.iter().any(|multi_line_mark| {
+                multi_line_mark.start_line_index == line_index
+                    || multi_line_mark.end_line_index == line_index
+            })
+    }
+}
+
+/// The columns of source code that are displayed in a snippet, used to
+/// truncate long lines to fit within `Config::max_width`.
+///
+/// ```text
+/// 1 │ ...ar c=a.map(function(d){return d.id}),e=b.filter(funct...
+/// ```
+struct Window {
+    /// The first display column that is shown.
+    start: usize,
+    /// The display column after the last one that is shown.
+    end: usize,
+}
+
+impl Window {
+    /// The number of columns taken up by the ellipses on truncated sides.
+    const ELLIPSIS_WIDTH: usize = 3;
+
+    /// A window that shows every column.
+    fn full() -> Window {
+        Window {
+            start: 0,
+            end: usize::max_value(),
+        }
+    }
+
+    /// Choose a window that is `width` columns wide, showing as much of the
+    /// focused columns as possible when the widest line doesn't fit.
+    fn new(width: usize, line_width: usize, focus: Range<usize>) -> Window {
+        if line_width <= width || width <= 2 * Window::ELLIPSIS_WIDTH {
+            return Window::full();
+        }
+
+        let start = if focus.end + Window::ELLIPSIS_WIDTH <= width {
+            0
+        } else {
+            // Center the focused columns between the ellipses, without
+            // moving past the end of the widest line
+            let visible_width = width - 2 * Window::ELLIPSIS_WIDTH;
+            let margin = visible_width.saturating_sub(focus.end - focus.start) / 2;
+            let start = focus.start.saturating_sub(Window::ELLIPSIS_WIDTH + margin);
+            std::cmp::min(start, line_width - width)
+        };
+
+        Window {
+            start,
+            end: start + width,
+        }
+    }
+
+    /// The column in the window where a column of the source code is displayed.
+    fn column(&self, column: usize) -> usize {
+        std::cmp::min(column.saturating_sub(self.start), self.end - self.start)
+    }
+
+    /// Write the part of a line of source code that is shown in the window,
+    /// with ellipses replacing the truncated sides. Segments of the line can
+    /// be highlighted, using byte ranges relative to the start of the line.
+    fn emit(
+        &self,
+        writer: &mut (impl WriteColor + ?Sized),
+        config: &Config,
+        source: &str,
+        segments: &[(Range<usize>, ColorSpec)],
+    ) -> io::Result<()> {
+        use std::io::Write;
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let is_truncated_end = config.width(source) > self.end;
+        let start = match self.start {
+            0 => 0,
+            start => {
+                write!(writer, "...")?;
+                start + Window::ELLIPSIS_WIDTH
+            }
+        };
+        let end = if is_truncated_end {
+            self.end - Window::ELLIPSIS_WIDTH
+        } else {
+            self.end
+        };
+
+        let mut current_style = None;
+        let mut column = 0;
+        for (byte_index, grapheme) in source.grapheme_indices(true) {
+            let next_column = column + config.width_at(column, grapheme);
+            if start <= column && next_column <= end {
+                let style = segments
+                    .iter()
+                    .find(|(range, _)| range.contains(&byte_index))
+                    .map(|(_, style)| style);
+                if style != current_style {
+                    match style {
+                        Some(style) => writer.set_color(style)?,
+                        None => writer.reset()?,
+                    }
+                    current_style = style;
+                }
+                match grapheme {
+                    // Tabs are expanded to the next tab stop of the source line,
+                    // which may not line up with the start of the window
+                    "\t" => write!(
+                        writer,
+                        "{space: >width$}",
+                        space = "",
+                        width = next_column - column,
+                    )?,
+                    _ => write!(config.source(writer), "{}", grapheme)?,
+                }
+            } else if column < end && start < next_column {
+                // Pad wide characters that only partially fit in the window
+                let width = std::cmp::min(next_column, end) - std::cmp::max(column, start);
+                write!(writer, "{space: >width$}", space = "", width = width)?;
+            }
+            column = next_column;
+            if column >= end {
+                break;
+            }
+        }
+        if current_style.is_some() {
+            writer.reset()?;
+        }
+
+        if is_truncated_end {
+            write!(writer, "...")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write a line of source code in full, highlighting it with the given
+/// highlighter.
+pub fn emit_source_line<FileId>(
+    writer: &mut (impl WriteColor + ?Sized),
+    config: &Config,
+    highlighter: Option<&dyn Highlighter<FileId>>,
+    file_id: FileId,
+    line_start: usize,
+    source: &str,
+) -> io::Result<()> {
+    let segments = highlight(writer, highlighter, file_id, line_start, source);
+    Window::full().emit(writer, config, source, &segments)
+}
+
+/// The highlighted segments of a line of source code that starts at the given
+/// byte index, as byte ranges relative to the start of the line.
+///
+/// Nothing is highlighted if the writer doesn't support colors.
+fn highlight<FileId>(
+    writer: &(impl WriteColor + ?Sized),
+    highlighter: Option<&dyn Highlighter<FileId>>,
+    file_id: FileId,
+    line_start: usize,
+    source: &str,
+) -> Vec<(Range<usize>, ColorSpec)> {
+    match highlighter {
+        Some(highlighter) if writer.supports_color() => {
+            let line_range = line_start..(line_start + source.len());
+            highlighter
+                .highlight(file_id, line_range)
+                .into_iter()
+                .map(|(range, style)| {
+                    let start = range.start.saturating_sub(line_start);
+                    let end = range.end.saturating_sub(line_start);
+                    (start..end, style)
+                })
+                .collect()
+        }
+        Some(_) | None => Vec::new(),
+    }
+}
+
+/// The index of the last line shown when displaying a number of lines of
+/// context after the given line, stopping at the end of the file.
+pub fn context_end_line_index<'files, F: Files<'files>>(
+    files: &'files F,
+    file_id: F::FileId,
+    line_index: usize,
+    context_lines: usize,
+) -> usize {
+    (line_index + 1..=line_index + context_lines)
+        .take_while(|line_index| match files.line(file_id, *line_index) {
+            // Don't show the empty line that follows a trailing newline
+            Some(line) => {
+                !line.source.as_ref().is_empty() || files.line(file_id, line_index + 1).is_some()
+            }
+            None => false,
+        })
+        .last()
+        .unwrap_or(line_index)
+}
+
+/// A mark that refers to a whole file.
+pub struct FileMark<'a> {
+    pub style: MarkStyle,
+    pub message: &'a str,
+}
+
 /// An underlined snippet of source code.
 ///
 /// ```text
@@ -31,10 +323,19 @@ pub struct Mark<'a> {
 ///   │         ^^ expected `Int` but found `String`
 ///   │
 /// ```
+///
+/// Snippets that only contain file marks are displayed without source code:
+///
+/// ```text
+///   ┌── test ───
+///   │ missing module header
+/// ```
 pub struct SourceSnippet<'a, 'files, F: Files<'files>> {
     gutter_padding: usize,
     file_id: F::FileId,
     mark_group: MarkGroup<'a, F::Origin>,
+    highlighter: Option<&'a dyn Highlighter<F::FileId>>,
+    is_continuation: bool,
 }
 
 impl<'a, 'files: 'a, F: Files<'files>> SourceSnippet<'a, 'files, F> {
@@ -47,17 +348,32 @@ impl<'a, 'files: 'a, F: Files<'files>> SourceSnippet<'a, 'files, F> {
             gutter_padding,
             file_id,
             mark_group,
+            highlighter: None,
+            is_continuation: false,
         }
     }
 
+    /// Mark the snippet as following another snippet of the same diagnostic.
+    pub fn with_continuation(mut self, is_continuation: bool) -> SourceSnippet<'a, 'files, F> {
+        self.is_continuation = is_continuation;
+        self
+    }
+
+    /// Highlight the source code using the given highlighter.
+    pub fn with_highlighter(
+        mut self,
+        highlighter: Option<&'a dyn Highlighter<F::FileId>>,
+    ) -> SourceSnippet<'a, 'files, F> {
+        self.highlighter = highlighter;
+        self
+    }
+
     pub fn emit(
         &self,
         files: &'files F,
         writer: &mut (impl WriteColor + ?Sized),
         config: &Config,
     ) -> io::Result<()> {
-        use std::io::Write;
-
         let line_index = |byte_index| files.line_index(self.file_id, byte_index);
         let line = |line_index| files.line(self.file_id, line_index);
 
@@ -67,33 +383,107 @@ impl<'a, 'files: 'a, F: Files<'files>> SourceSnippet<'a, 'files, F> {
         // ┌── test:2:9 ───
         // ```
 
-        Gutter::new(None, self.gutter_padding).emit(writer, config)?;
-        BorderTopLeft::new().emit(writer, config)?;
-        BorderTop::new(2).emit(writer, config)?;
-        write!(writer, " ")?;
-
-        {
+        let locus = {
             let origin = &self.mark_group.origin;
-            let start = self.mark_group.range.start;
-            let line_index = line_index(start).expect("locus_line_index");
-            let line = line(line_index).expect("locus_line");
+            match &self.mark_group.range {
+                Some(range) => {
+                    let start = range.start;
+                    let line_index = line_index(start).expect("locus_line_index");
+                    let line = line(line_index).expect("locus_line");
+
+                    Locus::new(origin, line.number, config.column_number(&line, start))
+                }
+                None => Locus::file(origin),
+            }
+        };
+        SnippetTop::new(self.gutter_padding, locus)
+            .with_continuation(self.is_continuation)
+            .emit(writer, config)?;
+
+        // Messages of the marks that refer to the whole file
+        //
+        // ```text
+        // │ missing module header
+        // ```
+
+        for file_mark in &self.mark_group.file_marks {
+            if file_mark.message.is_empty() {
+                continue;
+            }
 
-            Locus::new(&origin, line.number, line.column_number(start)).emit(writer, config)?;
+            Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+            BorderLeft::new().emit(writer, config)?;
+            write!(writer, " ")?;
+            writer.set_color(file_mark.style.label_style(config))?;
+            write!(writer, "{}", file_mark.message)?;
+            writer.reset()?;
+            NewLine::new().emit(writer, config)?;
         }
 
-        write!(writer, " ")?;
-        BorderTop::new(3).emit(writer, config)?;
-        NewLine::new().emit(writer, config)?;
+        if self.mark_group.marks.is_empty() {
+            return Ok(());
+        }
 
-        for (i, mark) in self.mark_group.marks.iter().enumerate() {
+        // Group the marks into the units that are displayed together. Marks
+        // are sorted by their start position, so overlapping multi-line marks
+        // are adjacent to each other.
+        let mut units = Vec::<MarkUnit<'_, '_>>::new();
+        let mut single_line_marks = Vec::new();
+        for mark in &self.mark_group.marks {
             let start_line_index = line_index(mark.range.start).expect("start_line_index");
             let end_line_index = line_index(mark.range.end).expect("end_line_index");
-            let start_line = line(start_line_index).expect("start_line");
-            let end_line = line(end_line_index).expect("end_line");
 
-            let start_source = start_line.source.as_ref();
-            let end_source = end_line.source.as_ref();
+            if start_line_index == end_line_index {
+                single_line_marks.push((start_line_index, mark));
+                continue;
+            }
+
+            match units.last_mut() {
+                Some(unit) if unit.contains_line(start_line_index) => {
+                    unit.push_multi_line(start_line_index, end_line_index, mark);
+                }
+                _ => {
+                    let mut unit = MarkUnit::new(start_line_index, end_line_index);
+                    unit.push_multi_line(start_line_index, end_line_index, mark);
+                    units.push(unit);
+                }
+            }
+        }
+        for (line_index, mark) in single_line_marks {
+            match units.iter_mut().find(|unit| unit.contains_line(line_index)) {
+                Some(unit) => unit.single_line_marks.push((line_index, mark)),
+                None => {
+                    let mut unit = MarkUnit::new(line_index, line_index);
+                    unit.single_line_marks.push((line_index, mark));
+                    units.push(unit);
+                }
+            }
+        }
+        units.sort_by_key(|unit| unit.start_line_index);
+
+        // Add the lines of context around each unit, merging the units whose
+        // lines overlap or touch into a single contiguous snippet.
+        let mut snippets = Vec::<MarkUnit<'_, '_>>::new();
+        for mut unit in units {
+            unit.start_line_index = unit
+                .start_line_index
+                .saturating_sub(config.before_label_lines);
+            unit.end_line_index = context_end_line_index(
+                files,
+                self.file_id,
+                unit.end_line_index,
+                config.after_label_lines,
+            );
+
+            match snippets.last_mut() {
+                Some(snippet) if unit.start_line_index <= snippet.end_line_index + 1 => {
+                    snippet.merge(unit);
+                }
+                _ => snippets.push(unit),
+            }
+        }
 
+        for (i, unit) in snippets.iter().enumerate() {
             // Code snippet
             //
             // ```text
@@ -111,136 +501,230 @@ impl<'a, 'files: 'a, F: Files<'files>> SourceSnippet<'a, 'files, F> {
             }
             NewLine::new().emit(writer, config)?;
 
-            // Write underlined source section
-            if start_line_index == end_line_index {
-                // Single line
+            // Lines that are near a label, taking into account the lines of context
+            let is_near_label = |line_index: usize| {
+                let start = line_index.saturating_sub(config.after_label_lines);
+                let end = line_index + config.before_label_lines;
+                (start..=end).any(|line_index| unit.is_labelled(line_index))
+            };
+
+            // The state of the multi-line mark columns for the current row
+            let mut columns = vec![None; unit.columns];
+
+            // Truncate long lines to the part around the marks
+            let window = match config.max_width {
+                None => Window::full(),
+                Some(max_width) => {
+                    let line_width = (unit.start_line_index..=unit.end_line_index)
+                        .map(|line_index| {
+                            let line = line(line_index).expect("line");
+                            config.width(line.source.as_ref().trim_end())
+                        })
+                        .max()
+                        .unwrap_or(0);
+
+                    // The display column of a byte index in its line
+                    let column = |byte_index: usize| {
+                        let line_index = line_index(byte_index).expect("line_index");
+                        let line = line(line_index).expect("line");
+                        let relative_index = byte_index - line.start;
+                        display_columns(
+                            config,
+                            line.source.as_ref(),
+                            relative_index..relative_index,
+                        )
+                        .start
+                    };
+                    let marks = unit.single_line_marks.iter().map(|(_, mark)| *mark).chain(
+                        unit.multi_line_marks
+                            .iter()
+                            .map(|multi_line_mark| multi_line_mark.mark),
+                    );
+                    let mut focus = usize::max_value()..0;
+                    for mark in marks {
+                        let (start, end) = (column(mark.range.start), column(mark.range.end));
+                        focus.start = std::cmp::min(focus.start, std::cmp::min(start, end));
+                        focus.end = std::cmp::max(focus.end, std::cmp::max(start, end) + 1);
+                    }
+
+                    // The gutter, border, and multi-line columns come before the source
+                    let prefix_width = self.gutter_padding + 4 + 2 * unit.columns;
+                    Window::new(max_width.saturating_sub(prefix_width), line_width, focus)
+                }
+            };
+
+            let mut line_index = unit.start_line_index;
+            while line_index <= unit.end_line_index {
+                // Collapse long stretches of lines that are away from any labels
                 //
                 // ```text
-                // 2 │ (+ test "")
-                //   │         ^^ expected `Int` but found `String`
+                //   │ │ ...
                 // ```
+                let unlabelled_lines = (line_index..=unit.end_line_index)
+                    .take_while(|line_index| !is_near_label(*line_index))
+                    .count();
+                if unlabelled_lines > config.max_unlabelled_lines {
+                    Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+                    BorderLeft::new().emit(writer, config)?;
+                    MultiLineColumns::new(&columns).emit(writer, config)?;
+                    write!(writer, " ...")?;
+                    NewLine::new().emit(writer, config)?;
 
-                let mark_start = mark.range.start - start_line.start;
-                let mark_end = mark.range.end - start_line.start;
-                let prefix_source = &start_source[..mark_start];
-                let marked_source = &start_source[mark_start..mark_end];
-
-                // Write line number and border
-                Gutter::new(start_line.number, self.gutter_padding).emit(writer, config)?;
-                BorderLeft::new().emit(writer, config)?;
+                    line_index += unlabelled_lines;
+                    continue;
+                }
 
-                // Write line source
-                write!(config.source(writer), " {}", start_source.trim_end())?;
-                NewLine::new().emit(writer, config)?;
+                let line = line(line_index).expect("line");
+                let source = line.source.as_ref();
 
-                // Write border, underline, and label
-                Gutter::new(None, self.gutter_padding).emit(writer, config)?;
-                BorderLeft::new().emit(writer, config)?;
-                Underline::new(mark.style, &prefix_source, &marked_source, &mark.message)
-                    .emit(writer, config)?;
-                NewLine::new().emit(writer, config)?;
-            } else {
-                // Multiple lines
+                // Multi-line marks that start at the beginning of the line
+                // are drawn alongside the source, and the rest are drawn
+                // with an underline running to their start.
                 //
                 // ```text
-                // 4 │   fizz₁ num = case (mod num 5) (mod num 3) of
-                //   │ ╭─────────────^
-                // 5 │ │     0 0 => "FizzBuzz"
-                // 6 │ │     0 _ => "Fizz"
-                // 7 │ │     _ 0 => "Buzz"
-                // 8 │ │     _ _ => num
-                //   │ ╰──────────────^ `case` clauses have incompatible types
+                // 4 │ ╭     case (mod num 5) (mod num 3) of
                 // ```
+                let mut top_marks = Vec::new();
+                for multi_line_mark in &unit.multi_line_marks {
+                    if multi_line_mark.start_line_index != line_index {
+                        continue;
+                    }
+                    let prefix_source = &source[..multi_line_mark.mark.range.start - line.start];
+                    if prefix_source.trim().is_empty() {
+                        let style = multi_line_mark.mark.style;
+                        columns[multi_line_mark.column] = Some(ColumnMark::TopLeft(style));
+                    } else {
+                        top_marks.push((multi_line_mark, prefix_source));
+                    }
+                }
+                top_marks.sort_by_key(|(multi_line_mark, _)| multi_line_mark.column);
 
-                let mark_start = mark.range.start - start_line.start;
-                let prefix_source = &start_source[..mark_start];
-
-                if prefix_source.trim().is_empty() {
-                    // Section is prefixed by empty space, so we don't need to take
-                    // up a new line.
-                    //
-                    // ```text
-                    // 4 │ ╭     case (mod num 5) (mod num 3) of
-                    // ```
-
-                    // Write line number, border, and underline
-                    Gutter::new(start_line.number, self.gutter_padding).emit(writer, config)?;
-                    BorderLeft::new().emit(writer, config)?;
-                    UnderlineTopLeft::new(mark.style).emit(writer, config)?;
-
-                    // Write source line
-                    write!(config.source(writer), " {}", start_source.trim_end())?;
-                    NewLine::new().emit(writer, config)?;
+                // Write line number, border, and source
+                //
+                // ```text
+                // 5 │ │ │     0 0 => "FizzBuzz"
+                // ```
+                Gutter::new(line.number, self.gutter_padding).emit(writer, config)?;
+                BorderLeft::new().emit(writer, config)?;
+                let trimmed_source = source.trim_end();
+                if trimmed_source.is_empty() {
+                    // Avoid trailing whitespace on blank lines
+                    let used_columns = columns
+                        .iter()
+                        .rposition(Option::is_some)
+                        .map_or(0, |column| column + 1);
+                    MultiLineColumns::new(&columns[..used_columns]).emit(writer, config)?;
                 } else {
-                    // There's source code in the prefix, so run an underline
-                    // underneath it to get to the start of the range.
-                    //
-                    // ```text
-                    // 4 │   fizz₁ num = case (mod num 5) (mod num 3) of
-                    //   │ ╭─────────────^
-                    // ```
-
-                    // Write line number and border
-                    Gutter::new(start_line.number, self.gutter_padding).emit(writer, config)?;
-                    BorderLeft::new().emit(writer, config)?;
-
-                    // Write source line
-                    write!(config.source(writer), "   {}", start_source.trim_end())?;
-                    NewLine::new().emit(writer, config)?;
+                    MultiLineColumns::new(&columns).emit(writer, config)?;
+                    let segments = highlight(
+                        writer,
+                        self.highlighter,
+                        self.file_id,
+                        line.start,
+                        trimmed_source,
+                    );
+
+                    write!(writer, " ")?;
+                    window.emit(writer, config, trimmed_source, &segments)?;
+                }
+                NewLine::new().emit(writer, config)?;
 
-                    // Write border and underline
-                    Gutter::new(None, self.gutter_padding).emit(writer, config)?;
-                    BorderLeft::new().emit(writer, config)?;
-                    UnderlineTop::new(mark.style, &prefix_source).emit(writer, config)?;
-                    NewLine::new().emit(writer, config)?;
+                for column in &mut columns {
+                    if let Some(ColumnMark::TopLeft(style)) = *column {
+                        *column = Some(ColumnMark::Left(style));
+                    }
                 }
 
-                // Write marked lines
+                // Write the underlines and labels of the single-line marks
                 //
                 // ```text
-                // 5 │ │     0 0 => "FizzBuzz"
-                // 6 │ │     0 _ => "Fizz"
-                // 7 │ │     _ 0 => "Buzz"
+                //   │ │            ^^^ expected `String`, found `Nat`
                 // ```
+                let single_line_marks = unit
+                    .single_line_marks
+                    .iter()
+                    .filter(|(mark_line_index, _)| *mark_line_index == line_index)
+                    .map(|(_, mark)| {
+                        let mark_range =
+                            (mark.range.start - line.start)..(mark.range.end - line.start);
+                        let columns = display_columns(config, source, mark_range);
+                        let start = window.column(columns.start);
+                        // We use `usize::max` here to ensure that we print at least one
+                        // underline character - even when we have a zero-length span.
+                        let end = usize::max(window.column(columns.end), start + 1);
+
+                        SingleLineMark {
+                            style: mark.style,
+                            start,
+                            end,
+                            message: mark.message,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if !single_line_marks.is_empty() {
+                    Underlines::new(self.gutter_padding, &columns, &single_line_marks)
+                        .emit(writer, config)?;
+                }
 
-                for line_index in (start_line_index + 1)..end_line_index {
-                    let marked_line = line(line_index).expect("marked_line");
+                // Write the tops of the multi-line marks that start partway
+                // through the line, running underneath the source to their start
+                //
+                // ```text
+                //   │ ╭─────────────^
+                // ```
+                for (multi_line_mark, prefix_source) in top_marks {
+                    let column = multi_line_mark.column;
+                    let style = multi_line_mark.mark.style;
 
-                    // Write line number, border, and underline
-                    Gutter::new(marked_line.number, self.gutter_padding).emit(writer, config)?;
+                    Gutter::new(None, self.gutter_padding).emit(writer, config)?;
                     BorderLeft::new().emit(writer, config)?;
-                    UnderlineLeft::new(mark.style).emit(writer, config)?;
-
-                    // Write marked source
-                    write!(writer, " {}", marked_line.source.as_ref().trim_end())?;
+                    MultiLineColumns::new(&columns[..column]).emit(writer, config)?;
+                    let prefix_len = prefix_source.len();
+                    let prefix_width = window
+                        .column(display_columns(config, source, prefix_len..prefix_len).start);
+                    UnderlineTop::new(style, unit.columns - column - 1, prefix_width)
+                        .emit(writer, config)?;
                     NewLine::new().emit(writer, config)?;
+
+                    columns[column] = Some(ColumnMark::Left(style));
                 }
 
-                // Write last marked line
+                // Write the bottoms of the multi-line marks that end on this
+                // line, from the innermost column outwards
                 //
                 // ```text
-                // 8 │ │     _ _ => num
                 //   │ ╰──────────────^ `case` clauses have incompatible types
                 // ```
+                let mut bottom_marks = unit
+                    .multi_line_marks
+                    .iter()
+                    .filter(|multi_line_mark| multi_line_mark.end_line_index == line_index)
+                    .collect::<Vec<_>>();
+                bottom_marks
+                    .sort_by_key(|multi_line_mark| std::cmp::Reverse(multi_line_mark.column));
+                for multi_line_mark in bottom_marks {
+                    let column = multi_line_mark.column;
+                    let mark = multi_line_mark.mark;
+                    let mark_end = mark.range.end - line.start;
+                    let marked_width =
+                        window.column(display_columns(config, source, 0..mark_end).end);
 
-                let mark_end = mark.range.end - end_line.start;
-                let marked_source = &end_source[..mark_end];
-
-                // Write line number, border, and underline
-                Gutter::new(end_line.number, self.gutter_padding).emit(writer, config)?;
-                BorderLeft::new().emit(writer, config)?;
-                UnderlineLeft::new(mark.style).emit(writer, config)?;
+                    Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+                    BorderLeft::new().emit(writer, config)?;
+                    MultiLineColumns::new(&columns[..column]).emit(writer, config)?;
+                    UnderlineBottom::new(
+                        mark.style,
+                        unit.columns - column - 1,
+                        marked_width,
+                        mark.message,
+                    )
+                    .emit(writer, config)?;
+                    NewLine::new().emit(writer, config)?;
 
-                // Write line source
-                write!(config.source(writer), " {}", end_source.trim_end())?;
-                NewLine::new().emit(writer, config)?;
+                    columns[column] = None;
+                }
 
-                // Write border, underline, and label
-                Gutter::new(None, self.gutter_padding).emit(writer, config)?;
-                BorderLeft::new().emit(writer, config)?;
-                UnderlineBottom::new(mark.style, &marked_source, &mark.message)
-                    .emit(writer, config)?;
-                NewLine::new().emit(writer, config)?;
+                line_index += 1;
             }
         }
 
@@ -0,0 +1,224 @@
+use std::io;
+use termcolor::{ColorSpec, WriteColor};
+
+use crate::diagnostic::Suggestion;
+use crate::files::Files;
+use crate::term::{Config, DisplayStyle};
+
+use super::{BorderLeft, Gutter, Locus, NewLine, SnippetTop};
+
+/// The kind of edit being made by a suggestion.
+#[derive(Copy, Clone)]
+enum EditKind {
+    Insertion,
+    Replacement,
+    Removal,
+}
+
+impl EditKind {
+    fn style(self, config: &Config) -> &ColorSpec {
+        match self {
+            EditKind::Insertion | EditKind::Replacement => &config.styles.suggestion_insertion,
+            EditKind::Removal => &config.styles.suggestion_removal,
+        }
+    }
+
+    fn marker_char(self, config: &Config) -> char {
+        match self {
+            EditKind::Insertion => config.chars.suggestion_insertion,
+            EditKind::Replacement => config.chars.suggestion_replacement,
+            EditKind::Removal => config.chars.suggestion_removal,
+        }
+    }
+}
+
+/// A snippet of source code with a suggested edit applied to it.
+///
+/// Inserted and replaced text is shown in the patched source, while removed
+/// text is shown in the original source.
+///
+/// ```text
+///   ┌── test:2:9 ───
+///   │
+/// 2 │ (+ test 1)
+///   │         ~
+///   │
+/// ```
+pub struct SuggestionSnippet<'a, 'files, F: Files<'files>> {
+    gutter_padding: usize,
+    suggestion: &'a Suggestion<F::FileId>,
+}
+
+impl<'a, 'files: 'a, F: Files<'files>> SuggestionSnippet<'a, 'files, F> {
+    pub fn new(
+        gutter_padding: usize,
+        suggestion: &'a Suggestion<F::FileId>,
+    ) -> SuggestionSnippet<'a, 'files, F> {
+        SuggestionSnippet {
+            gutter_padding,
+            suggestion,
+        }
+    }
+
+    pub fn emit(
+        &self,
+        files: &'files F,
+        writer: &mut (impl WriteColor + ?Sized),
+        config: &Config,
+    ) -> io::Result<()> {
+        use std::io::Write;
+
+        let file_id = self.suggestion.file_id;
+        let range = &self.suggestion.range;
+        let replacement = self.suggestion.replacement.as_str();
+
+        let start_line_index = files
+            .line_index(file_id, range.start)
+            .expect("start_line_index");
+        let mut end_line_index = files
+            .line_index(file_id, range.end)
+            .expect("end_line_index");
+        let start_line = files.line(file_id, start_line_index).expect("start_line");
+
+        // Avoid showing the following line for edits that end with a line break
+        if end_line_index > start_line_index {
+            let end_line = files.line(file_id, end_line_index).expect("end_line");
+            if end_line.start == range.end {
+                end_line_index -= 1;
+            }
+        }
+
+        // Top left border and locus.
+        //
+        // ```text
+        // ┌── test:2:9 ───
+        // │
+        // ```
+
+        match config.display_style {
+            // rustc omits the locus, as suggestions follow the snippets that
+            // they apply to
+            DisplayStyle::Rustc => {}
+            _ => {
+                let origin = files.origin(file_id).expect("origin");
+                let column_number = config.column_number(&start_line, range.start);
+                let locus = Locus::new(origin, start_line.number, column_number);
+                SnippetTop::new(self.gutter_padding, locus).emit(writer, config)?;
+            }
+        }
+
+        Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+        BorderLeft::new().emit(writer, config)?;
+        NewLine::new().emit(writer, config)?;
+
+        // Apply the edit to the lines that it covers, keeping track of the
+        // section of the resulting source that should be highlighted.
+
+        let mut source = String::new();
+        for line_index in start_line_index..=end_line_index {
+            let line = files.line(file_id, line_index).expect("line");
+            source.push_str(line.source.as_ref());
+        }
+
+        let edit_start = range.start - start_line.start;
+        let edit_end = range.end - start_line.start;
+
+        let (edit_kind, source, highlight) = if replacement.is_empty() {
+            (EditKind::Removal, source, edit_start..edit_end)
+        } else {
+            let edit_kind = if range.start == range.end {
+                EditKind::Insertion
+            } else {
+                EditKind::Replacement
+            };
+            let patched_source = format!(
+                "{}{}{}",
+                &source[..edit_start],
+                replacement,
+                &source[edit_end..],
+            );
+
+            (
+                edit_kind,
+                patched_source,
+                edit_start..edit_start + replacement.len(),
+            )
+        };
+
+        // Write the edited lines
+        //
+        // ```text
+        // 2 │ (+ test 1)
+        //   │         ~
+        // ```
+
+        let mut line_start = 0;
+
+        for (i, line_source) in source.split('\n').enumerate() {
+            let line_end = line_start + line_source.len();
+            // Skip the empty remainder following a trailing line break
+            if i > 0 && line_source.is_empty() && line_end == source.len() {
+                break;
+            }
+
+            let line_source = line_source.trim_end();
+            let is_highlighted = (highlight.start < line_end && highlight.end > line_start)
+                || (highlight.start == highlight.end && i == 0);
+            let (highlight_start, highlight_end) = if is_highlighted {
+                let highlight_start = usize::max(highlight.start, line_start) - line_start;
+                let highlight_end = usize::min(highlight.end, line_end) - line_start;
+                (
+                    usize::min(highlight_start, line_source.len()),
+                    usize::min(highlight_end, line_source.len()),
+                )
+            } else {
+                (0, 0)
+            };
+
+            let prefix_source = &line_source[..highlight_start];
+            let highlighted_source = &line_source[highlight_start..highlight_end];
+            let suffix_source = &line_source[highlight_end..];
+
+            // Write line number, border, and source
+            Gutter::new(start_line.number + i, self.gutter_padding).emit(writer, config)?;
+            BorderLeft::new().emit(writer, config)?;
+            write!(writer, " ")?;
+            let mut source_writer = config.source(writer);
+            write!(source_writer, "{}", prefix_source)?;
+            if !highlighted_source.is_empty() {
+                source_writer.set_color(edit_kind.style(config))?;
+                write!(source_writer, "{}", highlighted_source)?;
+                source_writer.reset()?;
+            }
+            write!(source_writer, "{}", suffix_source)?;
+            NewLine::new().emit(writer, config)?;
+
+            if is_highlighted {
+                // Write border and edit markers
+                Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+                BorderLeft::new().emit(writer, config)?;
+                let prefix_len = config.width(prefix_source);
+                write!(writer, " {space: >width$}", space = "", width = prefix_len)?;
+                writer.set_color(edit_kind.style(config))?;
+                // Ensure that at least one marker is printed, even for edits
+                // that only cover whitespace or line breaks.
+                let highlighted_len = config.width(&line_source[..highlight_end]) - prefix_len;
+                let marker_len = usize::max(highlighted_len, 1);
+                for _ in 0..marker_len {
+                    write!(writer, "{}", edit_kind.marker_char(config))?;
+                }
+                writer.reset()?;
+                NewLine::new().emit(writer, config)?;
+            }
+
+            line_start = line_end + 1;
+        }
+
+        // Write final border
+        Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+        BorderLeft::new().emit(writer, config)?;
+        NewLine::new().emit(writer, config)?;
+
+        Ok(())
+    }
+}
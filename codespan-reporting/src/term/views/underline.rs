@@ -1,17 +1,20 @@
 use std::io;
+use std::ops::Range;
 use termcolor::{ColorSpec, WriteColor};
 
 use crate::diagnostic::Severity;
 use crate::term::Config;
 
-#[derive(Copy, Clone)]
+use super::{BorderLeft, Gutter, NewLine};
+
+#[derive(Copy, Clone, PartialEq)]
 pub enum MarkStyle {
     Primary(Severity),
     Secondary,
 }
 
 impl MarkStyle {
-    pub fn label_style<'config>(self, config: &'config Config) -> &'config ColorSpec {
+    pub fn label_style(self, config: &Config) -> &ColorSpec {
         match self {
             MarkStyle::Primary(severity) => config.styles.primary_label(severity),
             MarkStyle::Secondary => &config.styles.secondary_label,
@@ -33,152 +36,356 @@ impl MarkStyle {
     }
 }
 
-/// The underline of a single source line.
+/// The display columns covered by a range of bytes in a line of source code.
+/// The range is widened to cover whole grapheme clusters, so that combining
+/// marks and emoji sequences are underlined along with the characters they are
+/// displayed with.
+pub fn display_columns(config: &Config, source: &str, range: Range<usize>) -> Range<usize> {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let mut start = std::cmp::min(range.start, source.len());
+    let mut end = std::cmp::min(range.end, source.len());
+    for (index, grapheme) in source.grapheme_indices(true) {
+        let grapheme_end = index + grapheme.len();
+        if index < start && start < grapheme_end {
+            start = index;
+        }
+        if index < end && end < grapheme_end {
+            end = grapheme_end;
+        }
+    }
+
+    config.width(&source[..start])..config.width(&source[..end])
+}
+
+/// A mark that covers part of a single source line, positioned using display
+/// columns.
+pub struct SingleLineMark<'a> {
+    pub style: MarkStyle,
+    /// The display column where the mark starts.
+    pub start: usize,
+    /// The display column where the mark ends. This is always greater than
+    /// `start`, so that zero-length marks are still visible.
+    pub end: usize,
+    pub message: &'a str,
+}
+
+/// The underlines of the marks on a single source line.
+///
+/// The message of the rightmost mark is displayed inline, with the messages of
+/// the remaining marks hanging below, attached to their marks by pointers:
 ///
 /// ```text
-///       ^^ expected `Int` but found `String`
+///   │     --- ^^^  ------- found `String`
+///   │     │   │
+///   │     │   expected `Int`
+///   │     function defined here
 /// ```
-pub struct Underline<'a> {
-    mark_style: MarkStyle,
-    prefix_source: &'a str,
-    highlighted_source: &'a str,
-    message: &'a str,
+pub struct Underlines<'a> {
+    gutter_padding: usize,
+    columns: &'a [Option<ColumnMark>],
+    marks: &'a [SingleLineMark<'a>],
+    /// Whether the primary caret only marks the first column of primary
+    /// marks, with the rest marked by the secondary caret.
+    is_caret_ranged: bool,
 }
 
-impl<'a> Underline<'a> {
+impl<'a> Underlines<'a> {
+    /// Create the underlines for some marks, which must be sorted by their
+    /// start columns.
     pub fn new(
-        mark_style: MarkStyle,
-        prefix_source: &'a str,
-        highlighted_source: &'a str,
-        message: &'a str,
-    ) -> Underline<'a> {
-        Underline {
-            mark_style,
-            prefix_source,
-            highlighted_source,
-            message,
+        gutter_padding: usize,
+        columns: &'a [Option<ColumnMark>],
+        marks: &'a [SingleLineMark<'a>],
+    ) -> Underlines<'a> {
+        Underlines {
+            gutter_padding,
+            columns,
+            marks,
+            is_caret_ranged: false,
         }
     }
 
+    /// Mark only the first column of primary marks with the primary caret,
+    /// continuing the mark with the secondary caret, in the way that GCC marks
+    /// ranges of source code.
+    ///
+    /// ```text
+    ///       ~~~ ^~~~~~~
+    /// ```
+    pub fn with_caret_ranges(mut self) -> Underlines<'a> {
+        self.is_caret_ranged = true;
+        self
+    }
+
     pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
-        let prefix_len = config.width(self.prefix_source);
-        write!(writer, " {space: >width$}", space = "", width = prefix_len)?;
+        // The rightmost mark has its message displayed inline, provided that
+        // no other underlines extend past the end of it.
+        let max_end = self.marks.iter().map(|mark| mark.end).max().unwrap_or(0);
+        let inline_mark = match self.marks.last() {
+            Some(mark) if mark.end == max_end && !mark.message.is_empty() => Some(mark),
+            Some(_) | None => None,
+        };
+        let hanging_marks = self
+            .marks
+            .iter()
+            .filter(|mark| !mark.message.is_empty())
+            .filter(|mark| inline_mark.map_or(true, |inline| !std::ptr::eq(*mark, inline)))
+            .collect::<Vec<_>>();
 
-        writer.set_color(self.mark_style.label_style(config))?;
-        // We use `usize::max` here to ensure that we print at least one
-        // underline character - even when we have a zero-length span.
-        let underline_len = usize::max(config.width(self.highlighted_source), 1);
-        for _ in 0..underline_len {
-            write!(writer, "{}", self.mark_style.caret_char(config))?;
+        // Underlines, with the inline message
+        //
+        // ```text
+        //       --- ^^^  ------- found `String`
+        // ```
+        Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+        BorderLeft::new().emit(writer, config)?;
+        MultiLineColumns::new(self.columns).emit(writer, config)?;
+        write!(writer, " ")?;
+
+        let mut current_style = None;
+        for column in 0..max_end {
+            // Primary marks take precedence over secondary marks when they overlap
+            let mark_style = self
+                .marks
+                .iter()
+                .filter(|mark| mark.start <= column && column < mark.end)
+                .map(|mark| mark.style)
+                .max_by_key(|mark_style| match mark_style {
+                    MarkStyle::Primary(_) => 1,
+                    MarkStyle::Secondary => 0,
+                });
+
+            if mark_style != current_style {
+                match mark_style {
+                    Some(mark_style) => writer.set_color(mark_style.label_style(config))?,
+                    None => writer.reset()?,
+                }
+                current_style = mark_style;
+            }
+            match mark_style {
+                Some(primary @ MarkStyle::Primary(_)) if self.is_caret_ranged => {
+                    let is_mark_start = self
+                        .marks
+                        .iter()
+                        .any(|mark| mark.style == primary && mark.start == column);
+                    if is_mark_start {
+                        write!(writer, "{}", config.chars.primary_caret)?;
+                    } else {
+                        write!(writer, "{}", config.chars.secondary_caret)?;
+                    }
+                }
+                Some(mark_style) => write!(writer, "{}", mark_style.caret_char(config))?,
+                None => write!(writer, " ")?,
+            }
         }
-        if !self.message.is_empty() {
-            write!(writer, " {}", self.message)?;
+        if let Some(mark) = inline_mark {
+            if current_style != Some(mark.style) {
+                writer.set_color(mark.style.label_style(config))?;
+            }
+            write!(writer, " {}", mark.message)?;
         }
         writer.reset()?;
+        NewLine::new().emit(writer, config)?;
+
+        if hanging_marks.is_empty() {
+            return Ok(());
+        }
+
+        // Pointers to the hanging messages
+        //
+        // ```text
+        //       │   │
+        // ```
+        Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+        BorderLeft::new().emit(writer, config)?;
+        MultiLineColumns::new(self.columns).emit(writer, config)?;
+        Pointers::new(&hanging_marks).emit(writer, config)?;
+        NewLine::new().emit(writer, config)?;
+
+        // Hanging messages, from right to left
+        //
+        // ```text
+        //       │   expected `Int`
+        //       function defined here
+        // ```
+        for (i, mark) in hanging_marks.iter().enumerate().rev() {
+            Gutter::new(None, self.gutter_padding).emit(writer, config)?;
+            BorderLeft::new().emit(writer, config)?;
+            MultiLineColumns::new(self.columns).emit(writer, config)?;
+            // Marks that share a start column with this one don't need pointers
+            let left_marks = (hanging_marks[..i].iter())
+                .take_while(|left_mark| left_mark.start < mark.start)
+                .count();
+            let pointers = Pointers::new(&hanging_marks[..left_marks]);
+            pointers.emit(writer, config)?;
+
+            let padding = mark.start - pointers.width();
+            write!(writer, "{space: >width$}", space = "", width = padding)?;
+            writer.set_color(mark.style.label_style(config))?;
+            write!(writer, "{}", mark.message)?;
+            writer.reset()?;
+            NewLine::new().emit(writer, config)?;
+        }
 
         Ok(())
     }
 }
 
-/// The top-left of a multi-line underline.
+/// Pointers from the underlines of some marks to their hanging messages.
 ///
 /// ```text
-///  ╭
+///       │   │
 /// ```
-pub struct UnderlineTopLeft {
-    mark_style: MarkStyle,
+struct Pointers<'a> {
+    marks: &'a [&'a SingleLineMark<'a>],
 }
 
-impl UnderlineTopLeft {
-    pub fn new(mark_style: MarkStyle) -> UnderlineTopLeft {
-        UnderlineTopLeft { mark_style }
+impl<'a> Pointers<'a> {
+    fn new(marks: &'a [&'a SingleLineMark<'a>]) -> Pointers<'a> {
+        Pointers { marks }
     }
 
-    pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
-        write!(writer, " ")?;
+    /// The number of columns taken up by the pointers.
+    fn width(&self) -> usize {
+        self.marks.last().map_or(0, |mark| mark.start + 1)
+    }
 
-        writer.set_color(self.mark_style.label_style(config))?;
-        write!(writer, "{}", config.chars.multiline_top_left)?;
-        writer.reset()?;
+    fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
+        let mut column = 0;
+        write!(writer, " ")?;
+        for mark in self.marks {
+            if mark.start < column {
+                // Marks that start at the same column share a pointer
+                continue;
+            }
+            write!(
+                writer,
+                "{space: >width$}",
+                space = "",
+                width = mark.start - column
+            )?;
+            writer.set_color(mark.style.label_style(config))?;
+            write!(writer, "{}", config.chars.pointer_left)?;
+            writer.reset()?;
+            column = mark.start + 1;
+        }
 
         Ok(())
     }
 }
 
-/// The top of a multi-line underline.
+/// The state of a multi-line mark's column, to the left of a row of source
+/// code or of underlines.
+#[derive(Copy, Clone)]
+pub enum ColumnMark {
+    /// The top-left corner of a mark that starts at the beginning of the
+    /// source line.
+    TopLeft(MarkStyle),
+    /// The left side of a mark that continues through the row.
+    Left(MarkStyle),
+}
+
+/// The columns of the multi-line marks that are active in a row. Each column
+/// takes up two characters, with columns that are not in use left blank.
 ///
 /// ```text
-///  ╭─────────────^
+///  │ ╭
 /// ```
-pub struct UnderlineTop<'a> {
-    mark_style: MarkStyle,
-    prefix_source: &'a str,
+pub struct MultiLineColumns<'a> {
+    columns: &'a [Option<ColumnMark>],
 }
 
-impl<'a> UnderlineTop<'a> {
-    pub fn new(mark_style: MarkStyle, prefix_source: &'a str) -> UnderlineTop<'a> {
-        UnderlineTop {
-            mark_style,
-            prefix_source,
-        }
+impl<'a> MultiLineColumns<'a> {
+    pub fn new(columns: &'a [Option<ColumnMark>]) -> MultiLineColumns<'a> {
+        MultiLineColumns { columns }
     }
 
     pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
-        write!(writer, " ")?;
-
-        writer.set_color(self.mark_style.label_style(config))?;
-        write!(writer, "{}", config.chars.multiline_top_left)?;
-        let underline_len = config.width(self.prefix_source) + 1;
-        for _ in 0..underline_len {
-            write!(writer, "{}", config.chars.multiline_top)?;
+        for column in self.columns {
+            write!(writer, " ")?;
+            match column {
+                None => write!(writer, " ")?,
+                Some(ColumnMark::TopLeft(mark_style)) => {
+                    writer.set_color(mark_style.label_style(config))?;
+                    write!(writer, "{}", config.chars.multiline_top_left)?;
+                    writer.reset()?;
+                }
+                Some(ColumnMark::Left(mark_style)) => {
+                    writer.set_color(mark_style.label_style(config))?;
+                    write!(writer, "{}", config.chars.multiline_left)?;
+                    writer.reset()?;
+                }
+            }
         }
-        write!(writer, "{}", self.mark_style.multiline_caret_char(config))?;
-        writer.reset()?;
 
         Ok(())
     }
 }
 
-/// The left of a multi-line underline.
-pub struct UnderlineLeft {
+/// The top of a multi-line underline, running across the columns to its right
+/// and the start of the source line.
+///
+/// ```text
+///  ╭─────────────^
+/// ```
+pub struct UnderlineTop {
     mark_style: MarkStyle,
+    crossed_columns: usize,
+    /// The display width of the source code before the start of the mark.
+    prefix_width: usize,
 }
 
-impl UnderlineLeft {
-    pub fn new(mark_style: MarkStyle) -> UnderlineLeft {
-        UnderlineLeft { mark_style }
+impl UnderlineTop {
+    pub fn new(mark_style: MarkStyle, crossed_columns: usize, prefix_width: usize) -> UnderlineTop {
+        UnderlineTop {
+            mark_style,
+            crossed_columns,
+            prefix_width,
+        }
     }
 
     pub fn emit(&self, writer: &mut (impl WriteColor + ?Sized), config: &Config) -> io::Result<()> {
         write!(writer, " ")?;
+
         writer.set_color(self.mark_style.label_style(config))?;
-        write!(writer, "{}", config.chars.multiline_left)?;
+        write!(writer, "{}", config.chars.multiline_top_left)?;
+        let underline_len = 2 * self.crossed_columns + self.prefix_width + 1;
+        for _ in 0..underline_len {
+            write!(writer, "{}", config.chars.multiline_top)?;
+        }
+        write!(writer, "{}", self.mark_style.multiline_caret_char(config))?;
         writer.reset()?;
 
         Ok(())
     }
 }
 
-/// The bottom of a multi-line underline.
+/// The bottom of a multi-line underline, running across the columns to its
+/// right and the end of the marked source.
 ///
 /// ```text
 ///  ╰──────────────^ `case` clauses have incompatible types
 /// ```
 pub struct UnderlineBottom<'a> {
     mark_style: MarkStyle,
-    highlighted_source: &'a str,
+    crossed_columns: usize,
+    /// The display width of the source code up to the end of the mark.
+    highlighted_width: usize,
     message: &'a str,
 }
 
 impl<'a> UnderlineBottom<'a> {
     pub fn new(
         mark_style: MarkStyle,
-        highlighted_source: &'a str,
+        crossed_columns: usize,
+        highlighted_width: usize,
         message: &'a str,
     ) -> UnderlineBottom<'a> {
         UnderlineBottom {
             mark_style,
-            highlighted_source,
+            crossed_columns,
+            highlighted_width,
             message,
         }
     }
@@ -188,7 +395,7 @@ impl<'a> UnderlineBottom<'a> {
 
         writer.set_color(self.mark_style.label_style(config))?;
         write!(writer, "{}", config.chars.multiline_bottom_left)?;
-        let width = config.width(self.highlighted_source);
+        let width = 2 * self.crossed_columns + self.highlighted_width;
         for _ in 0..width {
             write!(writer, "{}", config.chars.multiline_bottom)?;
         }
@@ -0,0 +1,74 @@
+use crate::term::Config;
+
+/// Split some text into lines that fit within the given display width,
+/// breaking at word boundaries. Words that are too long to fit are placed on
+/// lines of their own, and leading whitespace is preserved on the first line.
+pub fn wrap_words<'a>(config: &Config, text: &'a str, width: usize) -> Vec<&'a str> {
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    let mut line_end = 0;
+
+    for word in text.split_whitespace() {
+        let word_start = word.as_ptr() as usize - text.as_ptr() as usize;
+        let word_end = word_start + word.len();
+
+        if line_end > line_start && config.width(&text[line_start..word_end]) > width {
+            lines.push(&text[line_start..line_end]);
+            line_start = word_start;
+        }
+        line_end = word_end;
+    }
+    lines.push(&text[line_start..line_end]);
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_short_text() {
+        let config = Config::default();
+
+        assert_eq!(
+            wrap_words(&config, "a short message", 80),
+            ["a short message"]
+        );
+        assert_eq!(wrap_words(&config, "", 80), [""]);
+    }
+
+    #[test]
+    fn wrap_at_word_boundaries() {
+        let config = Config::default();
+
+        assert_eq!(
+            wrap_words(&config, "the quick brown fox jumps over the lazy dog", 15),
+            ["the quick brown", "fox jumps over", "the lazy dog"],
+        );
+    }
+
+    #[test]
+    fn wrap_long_words() {
+        let config = Config::default();
+
+        assert_eq!(
+            wrap_words(
+                &config,
+                "see https://example.com/a/very/long/path for details",
+                12
+            ),
+            ["see", "https://example.com/a/very/long/path", "for details"],
+        );
+    }
+
+    #[test]
+    fn wrap_preserves_indentation() {
+        let config = Config::default();
+
+        assert_eq!(
+            wrap_words(&config, "   found type `Nat`", 12),
+            ["   found", "type `Nat`"],
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! Output as the XML reports of [Checkstyle][checkstyle] and [JUnit][junit],
+//! which are understood by Jenkins and many other dashboards.
+//!
+//! The diagnostics are grouped by the origin of the file of their first primary
+//! label, in the order in which the files first appear. Diagnostics without
+//! labels are left out of Checkstyle reports, and are grouped under the
+//! `(no file)` test suite in JUnit reports.
+//!
+//! [checkstyle]: https://checkstyle.org/
+//! [junit]: https://github.com/testmoapp/junitxml
+//!
+//! # Example
+//!
+//! ```rust
+//! use codespan_reporting::diagnostic::{Diagnostic, Label};
+//! use codespan_reporting::files::SimpleFiles;
+//! use codespan_reporting::xml;
+//!
+//! let mut files = SimpleFiles::new();
+//! let file_id = files.add("src/main.fun", "let x = 1;");
+//!
+//! let diagnostics = vec![Diagnostic::warning()
+//!     .with_code("W0001")
+//!     .with_message("unused variable: `x`")
+//!     .with_labels(vec![Label::primary(file_id, 4..5)])];
+//!
+//! let mut checkstyle = Vec::new();
+//! xml::emit_checkstyle(&mut checkstyle, &files, &diagnostics).unwrap();
+//!
+//! let mut junit = Vec::new();
+//! xml::emit_junit(&mut junit, &files, &diagnostics).unwrap();
+//! ```
+
+use std::io;
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::escape::Escaped;
+use crate::files::{self, Files};
+use crate::term::severity_name;
+
+/// Emit a batch of diagnostics as a Checkstyle report, using the given writer
+/// and files.
+///
+/// ```xml
+/// <file name="src/main.fun">
+///   <error line="1" column="5" severity="warning" message="unused variable: `x`" source="W0001"/>
+/// </file>
+/// ```
+pub fn emit_checkstyle<'a, 'files, F>(
+    writer: &mut (impl io::Write + ?Sized),
+    files: &'files F,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic<F::FileId>>,
+) -> io::Result<()>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<checkstyle version="4.3">"#)?;
+    for (origin, entries) in group_by_origin(files, diagnostics) {
+        let origin = match origin {
+            Some(origin) => origin,
+            None => continue,
+        };
+        writeln!(writer, r#"  <file name="{}">"#, Escaped(&origin))?;
+        for entry in entries {
+            let diagnostic = entry.diagnostic;
+            write!(writer, "    <error")?;
+            match entry.position {
+                Some((line_number, column_number)) => write!(
+                    writer,
+                    r#" line="{}" column="{}""#,
+                    line_number, column_number
+                )?,
+                None => write!(writer, r#" line="1""#)?,
+            }
+            write!(
+                writer,
+                r#" severity="{}" message="{}""#,
+                checkstyle_severity(diagnostic.severity),
+                Escaped(&diagnostic.message_with_notes()),
+            )?;
+            if let Some(code) = &diagnostic.code {
+                write!(writer, r#" source="{}""#, Escaped(code))?;
+            }
+            writeln!(writer, "/>")?;
+        }
+        writeln!(writer, "  </file>")?;
+    }
+    writeln!(writer, "</checkstyle>")
+}
+
+/// Emit a batch of diagnostics as a JUnit report, using the given writer and
+/// files.
+///
+/// Each file is a test suite, with a test case for each diagnostic. Bugs are
+/// reported as errors, errors and warnings as failures, and notes and help as
+/// passing test cases with their text in `<system-out>`.
+///
+/// ```xml
+/// <testsuite name="src/main.fun" tests="1" failures="1" errors="0">
+///   <testcase classname="src/main.fun" name="W0001 at 1:5">
+///     <failure type="warning" message="unused variable: `x`">src/main.fun:1:5: warning[W0001]: unused variable: `x`</failure>
+///   </testcase>
+/// </testsuite>
+/// ```
+pub fn emit_junit<'a, 'files, F>(
+    writer: &mut (impl io::Write + ?Sized),
+    files: &'files F,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic<F::FileId>>,
+) -> io::Result<()>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    let groups = group_by_origin(files, diagnostics);
+    let count = |entries: &[Entry<'_, F::FileId>]| {
+        let count_outcome = |outcome| {
+            entries
+                .iter()
+                .filter(|entry| junit_outcome(entry.diagnostic.severity) == outcome)
+                .count()
+        };
+        (count_outcome(Some("failure")), count_outcome(Some("error")))
+    };
+    let tests = groups
+        .iter()
+        .map(|(_, entries)| entries.len())
+        .sum::<usize>();
+
+    let (failures, errors) = groups
+        .iter()
+        .map(|(_, entries)| count(entries))
+        .fold((0, 0), |(failures, errors), counts| {
+            (failures + counts.0, errors + counts.1)
+        });
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuites tests="{}" failures="{}" errors="{}">"#,
+        tests, failures, errors,
+    )?;
+    for (origin, entries) in &groups {
+        let (failures, errors) = count(entries);
+        let suite = origin.as_ref().map_or(NO_FILE_SUITE, String::as_str);
+        writeln!(
+            writer,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}">"#,
+            Escaped(suite),
+            entries.len(),
+            failures,
+            errors,
+        )?;
+        for entry in entries {
+            let diagnostic = entry.diagnostic;
+            let check = match &diagnostic.code {
+                Some(code) => code.as_str(),
+                None => severity_name(diagnostic.severity),
+            };
+            let (name, locus) = match (origin, entry.position) {
+                (Some(origin), Some((line_number, column_number))) => (
+                    format!("{} at {}:{}", check, line_number, column_number),
+                    Some(format!("{}:{}:{}", origin, line_number, column_number)),
+                ),
+                (origin, _) => (check.to_owned(), origin.clone()),
+            };
+            let mut header = severity_name(diagnostic.severity).to_owned();
+            if let Some(code) = &diagnostic.code {
+                header.push_str(&format!("[{}]", code));
+            }
+            let text = match locus {
+                None => format!("{}: {}", header, diagnostic.message_with_notes()),
+                Some(locus) => {
+                    format!("{}: {}: {}", locus, header, diagnostic.message_with_notes())
+                }
+            };
+
+            writeln!(
+                writer,
+                r#"    <testcase classname="{}" name="{}">"#,
+                Escaped(suite),
+                Escaped(&name),
+            )?;
+            match junit_outcome(diagnostic.severity) {
+                Some(element) => writeln!(
+                    writer,
+                    r#"      <{element} type="{}" message="{}">{}</{element}>"#,
+                    severity_name(diagnostic.severity),
+                    Escaped(&diagnostic.message),
+                    Escaped(&text),
+                    element = element,
+                )?,
+                None => writeln!(writer, "      <system-out>{}</system-out>", Escaped(&text))?,
+            }
+            writeln!(writer, "    </testcase>")?;
+        }
+        writeln!(writer, "  </testsuite>")?;
+    }
+    writeln!(writer, "</testsuites>")
+}
+
+/// The name of the JUnit test suite of diagnostics without labels.
+const NO_FILE_SUITE: &str = "(no file)";
+
+/// A diagnostic, and the line and column numbers of its first primary label.
+struct Entry<'a, FileId> {
+    diagnostic: &'a Diagnostic<FileId>,
+    position: Option<(usize, usize)>,
+}
+
+/// The origin of a file, or `None` for diagnostics without labels, and the
+/// diagnostics in it.
+type Group<'a, FileId> = (Option<String>, Vec<Entry<'a, FileId>>);
+
+/// Group the diagnostics by the origin of the file of their first primary
+/// label, or their first label if they have no primary labels. Diagnostics
+/// without labels are grouped under `None`.
+fn group_by_origin<'a, 'files, F>(
+    files: &'files F,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic<F::FileId>>,
+) -> Vec<Group<'a, F::FileId>>
+where
+    F: Files<'files>,
+    F::FileId: 'a,
+{
+    let mut groups = Vec::<Group<'a, F::FileId>>::new();
+
+    for diagnostic in diagnostics {
+        let (origin, position) = match diagnostic.main_label() {
+            None => (None, None),
+            Some(label) => {
+                let origin = files.origin(label.file_id).expect("origin").to_string();
+                let position = label
+                    .range
+                    .as_ref()
+                    .map(|range| files::position(files, label.file_id, range.start));
+                (Some(origin), position)
+            }
+        };
+
+        let entry = Entry {
+            diagnostic,
+            position,
+        };
+        match groups.iter_mut().find(|(other, _)| *other == origin) {
+            Some((_, entries)) => entries.push(entry),
+            None => groups.push((origin, vec![entry])),
+        }
+    }
+
+    groups
+}
+
+/// The element that a test case of a diagnostic with the given severity is
+/// reported with, or `None` if the test case passes.
+fn junit_outcome(severity: Severity) -> Option<&'static str> {
+    match severity {
+        Severity::Bug => Some("error"),
+        Severity::Error | Severity::Warning => Some("failure"),
+        Severity::Note | Severity::Help => None,
+    }
+}
+
+/// The Checkstyle severity of a severity.
+fn checkstyle_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "info",
+    }
+}
@@ -0,0 +1,34 @@
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::github;
+
+mod support;
+
+use self::support::{NAT_FILE_ID, TEST_FILE_ID};
+
+#[test]
+fn multifile() {
+    let mut data = support::report_data("src/Test,1:2/");
+    data.diagnostics[2]
+        .notes
+        .push("expected type `Nat`\r\n   found type `String`".to_owned());
+    data.diagnostics.extend(vec![
+        Diagnostic::warning()
+            .with_message("overlapping patterns: `succ n₂`\n100% covered")
+            .with_labels(vec![
+                Label::primary(NAT_FILE_ID, 280..289),
+                Label::primary(NAT_FILE_ID, 309..318),
+            ]),
+        Diagnostic::warning()
+            .with_message("function `_+_` is never used")
+            .with_labels(vec![Label::secondary(NAT_FILE_ID, 130..155)]),
+        Diagnostic::help().with_message("run with `--verbose` for more details"),
+        Diagnostic::note()
+            .with_message("file was not formatted")
+            .with_labels(vec![Label::primary_file(TEST_FILE_ID)]),
+    ]);
+
+    let mut output = Vec::new();
+    github::emit(&mut output, &data.files, &data.diagnostics).unwrap();
+
+    insta::assert_snapshot!("multifile", String::from_utf8(output).unwrap());
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "gitlab")]
+
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::gitlab;
+
+mod support;
+
+use self::support::{NAT_FILE_ID, TEST_FILE_ID};
+
+fn emit(prelude: &str) -> String {
+    let mut data = support::report_data_with_prelude("src/", prelude);
+    let unexpected_type = data.diagnostics[2].clone();
+    data.diagnostics.extend(vec![
+        // The same issue, reported twice
+        unexpected_type,
+        Diagnostic::bug()
+            .with_message("failed to resolve `infixl`")
+            .with_labels(vec![Label::secondary(NAT_FILE_ID, 112..118)]),
+        Diagnostic::warning()
+            .with_message("missing module documentation")
+            .with_labels(vec![Label::primary_file(TEST_FILE_ID)]),
+        Diagnostic::help().with_message("run with `--verbose` for more details"),
+    ]);
+
+    let mut output = Vec::new();
+    gitlab::emit(&mut output, &data.files, &data.diagnostics).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+fn fingerprints(report: &str) -> Vec<&str> {
+    report
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("\"fingerprint\""))
+        .collect()
+}
+
+#[test]
+fn multifile() {
+    insta::assert_snapshot!("multifile", emit(""));
+}
+
+#[test]
+fn fingerprints_are_unique() {
+    let report = emit("");
+    let mut fingerprints = fingerprints(&report);
+    fingerprints.sort();
+    fingerprints.dedup();
+
+    assert_eq!(fingerprints.len(), 6);
+}
+
+#[test]
+fn fingerprints_ignore_moved_lines() {
+    let report = emit("");
+    let moved_report = emit("-- Tests for `Data.Nat`\n\n");
+
+    assert_ne!(report, moved_report);
+    assert_eq!(fingerprints(&report), fingerprints(&moved_report));
+}
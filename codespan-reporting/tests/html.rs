@@ -0,0 +1,59 @@
+use codespan_reporting::diagnostic::{Diagnostic, Label, SubDiagnostic};
+use codespan_reporting::files::SimpleFiles;
+use codespan_reporting::html::{self, Config};
+use codespan_reporting::term::{self, Hyperlinks};
+
+mod support;
+
+use self::support::{TestData, TEST_FILE_ID};
+
+fn test_data() -> TestData<'static, SimpleFiles<String, String>> {
+    let mut data = support::report_data("/home/user/fun & games/");
+    data.diagnostics.push(
+        Diagnostic::warning()
+            .with_message("unknown operator: `+`")
+            .with_labels(vec![Label::primary(TEST_FILE_ID, 35..36)])
+            .with_children(vec![
+                SubDiagnostic::help().with_message("did you mean `_+_`?")
+            ]),
+    );
+    data
+}
+
+#[test]
+fn multifile() {
+    let data = test_data();
+
+    let mut output = Vec::new();
+    html::emit(
+        &mut output,
+        &Config::default(),
+        &data.files,
+        &data.diagnostics,
+    )
+    .unwrap();
+
+    insta::assert_snapshot!("multifile", String::from_utf8(output).unwrap());
+}
+
+#[test]
+fn hyperlinks() {
+    let data = test_data();
+
+    let config = Config {
+        inline_stylesheet: false,
+        anchor_prefix: "build-42-".to_owned(),
+        ..Config::new(term::Config {
+            hyperlinks: Some(
+                Hyperlinks::new()
+                    .with_locus_template("vscode://file{path}:{line}:{col}")
+                    .with_code_template("https://example.com/errors/{code}?a=1&b=2"),
+            ),
+            ..term::Config::default()
+        })
+    };
+    let mut output = Vec::new();
+    html::emit(&mut output, &config, &data.files, &data.diagnostics).unwrap();
+
+    insta::assert_snapshot!("hyperlinks", String::from_utf8(output).unwrap());
+}
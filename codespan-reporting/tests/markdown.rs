@@ -0,0 +1,59 @@
+use codespan_reporting::diagnostic::{Diagnostic, Label, SubDiagnostic, Suggestion};
+use codespan_reporting::markdown::{self, Config};
+
+mod support;
+
+use self::support::{NAT_FILE_ID, TEST_FILE_ID};
+
+fn emit(config: &Config) -> String {
+    let mut data = support::report_data("src/Test Data/");
+
+    let unexpected_type = &mut data.diagnostics[2];
+    unexpected_type.notes.push(unindent::unindent(
+        "
+            expected type `Nat`
+               found type `String`
+        ",
+    ));
+    unexpected_type.children.push(
+        SubDiagnostic::note()
+            .with_message("`_+_` is defined here")
+            .with_labels(vec![Label::primary(NAT_FILE_ID, 130..133)]),
+    );
+    unexpected_type.suggestions.push(
+        Suggestion::new(TEST_FILE_ID, 37..44, "\"hello\".len")
+            .with_message("use the length of the string"),
+    );
+    data.diagnostics.push(
+        Diagnostic::warning()
+            .with_message("ambiguous operator: +\nthe operators in scope are <_+_> and <_-_>")
+            .with_labels(vec![Label::primary(TEST_FILE_ID, 35..36)]),
+    );
+
+    let mut output = Vec::new();
+    for (i, diagnostic) in data.diagnostics.iter().enumerate() {
+        if i > 0 {
+            output.push(b'\n');
+        }
+        markdown::emit(&mut output, config, &data.files, diagnostic).unwrap();
+    }
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn multifile() {
+    insta::assert_snapshot!("multifile", emit(&Config::default()));
+}
+
+#[test]
+fn permalinks() {
+    let config = Config {
+        heading_level: 2,
+        permalink_template: Some(
+            "https://github.com/owner/repo/blob/0123abc/{path}#L{line}".to_owned(),
+        ),
+        ..Config::default()
+    };
+
+    insta::assert_snapshot!("permalinks", emit(&config));
+}
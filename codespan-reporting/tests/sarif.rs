@@ -0,0 +1,32 @@
+#![cfg(feature = "sarif")]
+
+use codespan_reporting::diagnostic::{Applicability, Label, SubDiagnostic, Suggestion};
+use codespan_reporting::sarif::{self, Config};
+
+mod support;
+
+use self::support::NAT_FILE_ID;
+
+#[test]
+fn multifile() {
+    let mut data = support::report_data("Test Files/");
+    data.diagnostics[0].suggestions.push(
+        Suggestion::new(NAT_FILE_ID, 96..102, "NATURAL")
+            .with_message("use the similarly named builtin")
+            .with_applicability(Applicability::MaybeIncorrect),
+    );
+    data.diagnostics[2].children.push(
+        SubDiagnostic::note()
+            .with_message("`_+_` is declared with a fixity here")
+            .with_labels(vec![Label::primary(NAT_FILE_ID, 121..124)]),
+    );
+
+    let config = Config {
+        tool_version: Some("0.1.0".to_owned()),
+        ..Config::new("fun")
+    };
+    let mut output = Vec::new();
+    sarif::emit(&mut output, &config, &data.files, &data.diagnostics).unwrap();
+
+    insta::assert_snapshot!("multifile", String::from_utf8(output).unwrap());
+}
@@ -68,6 +68,10 @@ impl WriteColor for ColorBuffer {
         true
     }
 
+    fn supports_hyperlinks(&self) -> bool {
+        true
+    }
+
     fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
         #![allow(unused_assignments)]
 
@@ -92,7 +96,7 @@ impl WriteColor for ColorBuffer {
             }
 
             Ok(false)
-        };
+        }
 
         if let Some(fg) = spec.fg() {
             first = write_first(first, self)?;
@@ -1,6 +1,9 @@
-use codespan_reporting::diagnostic::Diagnostic;
-use codespan_reporting::files::Files;
-use codespan_reporting::term::{emit, Config};
+// Each test crate only uses some of the helpers
+#![allow(dead_code)]
+
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::files::{Files, SimpleFiles};
+use codespan_reporting::term::{emit, emit_explanation, emit_highlighted, Config, Highlighter};
 use termcolor::{Buffer, WriteColor};
 
 mod color_buffer;
@@ -15,17 +18,151 @@ pub struct TestData<'files, F: Files<'files>> {
 impl<'files, F: Files<'files>> TestData<'files, F> {
     fn emit<W: WriteColor>(&'files self, mut writer: W, config: &Config) -> W {
         for diagnostic in &self.diagnostics {
-            emit(&mut writer, config, &self.files, &diagnostic).unwrap();
+            emit(&mut writer, config, &self.files, diagnostic).unwrap();
         }
         writer
     }
 
     pub fn emit_color(&'files self, config: &Config) -> String {
-        self.emit(ColorBuffer::new(), &config).into_string()
+        self.emit(ColorBuffer::new(), config).into_string()
     }
 
     pub fn emit_no_color(&'files self, config: &Config) -> String {
-        let buffer = self.emit(Buffer::no_color(), &config);
+        let buffer = self.emit(Buffer::no_color(), config);
         String::from_utf8_lossy(buffer.as_slice()).into_owned()
     }
+
+    fn emit_highlighted<W: WriteColor>(
+        &'files self,
+        mut writer: W,
+        config: &Config,
+        highlighter: &dyn Highlighter<F::FileId>,
+    ) -> W {
+        for diagnostic in &self.diagnostics {
+            emit_highlighted(&mut writer, config, &self.files, highlighter, diagnostic).unwrap();
+        }
+        writer
+    }
+
+    pub fn emit_highlighted_color(
+        &'files self,
+        config: &Config,
+        highlighter: &dyn Highlighter<F::FileId>,
+    ) -> String {
+        self.emit_highlighted(ColorBuffer::new(), config, highlighter)
+            .into_string()
+    }
+
+    pub fn emit_highlighted_no_color(
+        &'files self,
+        config: &Config,
+        highlighter: &dyn Highlighter<F::FileId>,
+    ) -> String {
+        let buffer = self.emit_highlighted(Buffer::no_color(), config, highlighter);
+        String::from_utf8_lossy(buffer.as_slice()).into_owned()
+    }
+}
+
+/// The file id of `Data/Nat.fun` in the report test data.
+pub const NAT_FILE_ID: usize = 0;
+/// The file id of `Test.fun` in the report test data.
+pub const TEST_FILE_ID: usize = 1;
+
+/// Test data for the report formats, with diagnostics across two files,
+/// `Data/Nat.fun` and `Test.fun`, whose origins are prefixed by `root`.
+pub fn report_data(root: &str) -> TestData<'static, SimpleFiles<String, String>> {
+    report_data_with_prelude(root, "")
+}
+
+/// The report test data, with `prelude` inserted at the start of
+/// `Test.fun` to move the code after it.
+pub fn report_data_with_prelude(
+    root: &str,
+    prelude: &str,
+) -> TestData<'static, SimpleFiles<String, String>> {
+    let mut files = SimpleFiles::new();
+
+    let file_id1 = files.add(
+        format!("{}Data/Nat.fun", root),
+        unindent::unindent(
+            "
+                module Data.Nat where
+
+                data Nat : Type where
+                    zero : Nat
+                    succ : Nat → Nat
+
+                {-# BUILTIN NATRAL Nat #-}
+
+                infixl 6 _+_ _-_
+
+                _+_ : Nat → Nat → Nat
+                zero    + n₂ = n₂
+                succ n₁ + n₂ = succ (n₁ + n₂)
+
+                _-_ : Nat → Nat → Nat
+                n₁      - zero    = n₁
+                zero    - succ n₂ = zero
+                succ n₁ - succ n₂ = n₁ - n₂
+            ",
+        ),
+    );
+
+    let source = unindent::unindent(
+        r#"
+            module Test where
+
+            _ : Nat
+            _ = 123 + "hello"
+        "#,
+    );
+    let offset = prelude.len();
+    let file_id2 = files.add(
+        format!("{}Test.fun", root),
+        format!("{}{}", prelude, source),
+    );
+
+    let diagnostics = vec![
+        // Unknown builtin error
+        Diagnostic::error()
+            .with_message("unknown builtin: `NATRAL`")
+            .with_code("E0002")
+            .with_labels(vec![
+                Label::primary(file_id1, 96..102).with_message("unknown builtin")
+            ])
+            .with_notes(vec![
+                "there is a builtin with a similar name: `NATURAL`".to_owned()
+            ]),
+        // Unused parameter warning
+        Diagnostic::warning()
+            .with_message("unused parameter pattern: `n₂`")
+            .with_labels(vec![
+                Label::primary(file_id1, 285..289).with_message("unused parameter")
+            ])
+            .with_notes(vec!["consider using a wildcard pattern: `_`".to_owned()]),
+        // Unexpected type error
+        Diagnostic::error()
+            .with_message("unexpected type in application of `_+_`")
+            .with_code("E0001")
+            .with_labels(vec![
+                Label::primary(file_id2, offset + 37..offset + 44)
+                    .with_message("expected `Nat`, found `String`"),
+                Label::secondary(file_id1, 130..155)
+                    .with_message("based on the definition of `_+_`"),
+            ]),
+    ];
+
+    TestData { files, diagnostics }
+}
+
+pub fn explanation_color(config: &Config, explanation: &str) -> String {
+    let mut writer = ColorBuffer::new();
+    emit_explanation(&mut writer, config, explanation).unwrap();
+    writer.into_string()
+}
+
+pub fn explanation_no_color(config: &Config, explanation: &str) -> String {
+    let mut writer = Buffer::no_color();
+    emit_explanation(&mut writer, config, explanation).unwrap();
+    String::from_utf8_lossy(writer.as_slice()).into_owned()
 }
@@ -1,6 +1,8 @@
-use codespan_reporting::diagnostic::{Diagnostic, Label};
-use codespan_reporting::files::{SimpleFile, SimpleFiles};
-use codespan_reporting::term::{termcolor::Color, Config, DisplayStyle, Styles};
+use codespan_reporting::diagnostic::{Applicability, Diagnostic, Label, SubDiagnostic, Suggestion};
+use codespan_reporting::files::{ColumnUnit, SimpleFile, SimpleFiles};
+use codespan_reporting::term::{
+    termcolor::Color, Config, DisplayStyle, EscapeStyle, Hyperlinks, Preset, Styles,
+};
 
 mod support;
 
@@ -59,6 +61,50 @@ macro_rules! test_emit {
             insta::assert_snapshot!("short_no_color", TEST_DATA.emit_no_color(&config));
         }
     };
+    (rustc_no_color) => {
+        #[test]
+        fn rustc_no_color() {
+            let config = Config {
+                display_style: DisplayStyle::Rustc,
+                ..TEST_CONFIG.clone()
+            };
+
+            insta::assert_snapshot!("rustc_no_color", TEST_DATA.emit_no_color(&config));
+        }
+    };
+    (gcc_no_color) => {
+        #[test]
+        fn gcc_no_color() {
+            let config = Config {
+                display_style: DisplayStyle::Gcc,
+                ..TEST_CONFIG.clone()
+            };
+
+            insta::assert_snapshot!("gcc_no_color", TEST_DATA.emit_no_color(&config));
+        }
+    };
+    (vim_no_color) => {
+        #[test]
+        fn vim_no_color() {
+            let config = Config {
+                display_style: DisplayStyle::Vim,
+                ..TEST_CONFIG.clone()
+            };
+
+            insta::assert_snapshot!("vim_no_color", TEST_DATA.emit_no_color(&config));
+        }
+    };
+    (emacs_no_color) => {
+        #[test]
+        fn emacs_no_color() {
+            let config = Config {
+                display_style: DisplayStyle::Emacs,
+                ..TEST_CONFIG.clone()
+            };
+
+            insta::assert_snapshot!("emacs_no_color", TEST_DATA.emit_no_color(&config));
+        }
+    };
 }
 
 mod empty {
@@ -133,6 +179,10 @@ mod message_and_notes {
     test_emit!(short_color);
     test_emit!(rich_no_color);
     test_emit!(short_no_color);
+    test_emit!(rustc_no_color);
+    test_emit!(gcc_no_color);
+    test_emit!(vim_no_color);
+    test_emit!(emacs_no_color);
 }
 
 mod empty_spans {
@@ -241,6 +291,174 @@ mod multifile {
     test_emit!(short_color);
     test_emit!(rich_no_color);
     test_emit!(short_no_color);
+    test_emit!(rustc_no_color);
+    test_emit!(gcc_no_color);
+    test_emit!(vim_no_color);
+    test_emit!(emacs_no_color);
+}
+
+mod file_labels {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, &'static str>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id1 = files.add("Data/Nat.fun", "data Nat : Type where\n");
+            let file_id2 = files.add("Test.fun", "module Test where\n\nimport Data.Nat\n");
+            let file_id3 = files.add("Binary.fun", "");
+
+            let diagnostics = vec![
+                Diagnostic::error()
+                    .with_message("file is not valid UTF-8")
+                    .with_labels(vec![Label::primary_file(file_id3)]),
+                Diagnostic::error()
+                    .with_message("missing module header")
+                    .with_labels(vec![
+                        Label::primary_file(file_id1).with_message("expected `module Data.Nat where`"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("unused import")
+                    .with_labels(vec![
+                        Label::primary(file_id2, 19..34).with_message("this import is unused"),
+                        Label::secondary_file(file_id1).with_message("imported module"),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    test_emit!(rich_color);
+    test_emit!(short_color);
+    test_emit!(rich_no_color);
+    test_emit!(short_no_color);
+    test_emit!(rustc_no_color);
+    test_emit!(gcc_no_color);
+    test_emit!(vim_no_color);
+    test_emit!(emacs_no_color);
+}
+
+mod same_line {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "one_line.rs",
+                unindent::unindent(r#"
+                    fn main() {
+                        let mut v = vec![Some("foo"), Some("bar")];
+                        v.push(v.pop().unwrap());
+                    }
+                "#),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::error()
+                    .with_code("E0499")
+                    .with_message("cannot borrow `v` as mutable more than once at a time")
+                    .with_labels(vec![
+                        Label::primary(file_id, 71..72)
+                            .with_message("second mutable borrow occurs here"),
+                        Label::secondary(file_id, 64..65)
+                            .with_message("first borrow later used by call"),
+                        Label::secondary(file_id, 66..70)
+                            .with_message("first mutable borrow occurs here"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("overlapping labels")
+                    .with_labels(vec![
+                        Label::secondary(file_id, 20..25),
+                        Label::primary(file_id, 24..31).with_message("primary label"),
+                        Label::secondary(file_id, 33..37).with_message("secondary label"),
+                        Label::secondary(file_id, 46..51),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    test_emit!(rich_color);
+    test_emit!(short_color);
+    test_emit!(rich_no_color);
+    test_emit!(short_no_color);
+    test_emit!(rustc_no_color);
+    test_emit!(gcc_no_color);
+    test_emit!(vim_no_color);
+    test_emit!(emacs_no_color);
+}
+
+mod multiline_overlapping {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "nested.rs",
+                unindent::unindent(r#"
+                    fn main() {
+                        let x = match value {
+                            Some(n) => {
+                                n + 1
+                            }
+                            None => 0,
+                        };
+                        let y = x;
+                    }
+                "#),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::error()
+                    .with_code("E0308")
+                    .with_message("`match` arms have incompatible types")
+                    .with_labels(vec![
+                        Label::secondary(file_id, 24..111)
+                            .with_message("`match` arms have incompatible types"),
+                        Label::secondary(file_id, 57..86)
+                            .with_message("this is found to be of type `Int`"),
+                        Label::primary(file_id, 71..76)
+                            .with_message("expected `Int`, found `Nat`"),
+                        Label::primary(file_id, 104..105)
+                            .with_message("expected `Nat`, found `Int`"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("overlapping labels")
+                    .with_labels(vec![
+                        Label::primary(file_id, 0..129).with_message("whole function"),
+                        Label::primary(file_id, 16..76).with_message("first label"),
+                        Label::secondary(file_id, 46..105).with_message("second label"),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    test_emit!(rich_color);
+    test_emit!(short_color);
+    test_emit!(rich_no_color);
+    test_emit!(short_no_color);
+    test_emit!(rustc_no_color);
+    test_emit!(gcc_no_color);
+    test_emit!(vim_no_color);
+    test_emit!(emacs_no_color);
+
+    #[test]
+    fn ascii_no_color() {
+        let config = Config {
+            chars: Preset::Ascii.chars(),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("ascii_no_color", TEST_DATA.emit_no_color(&config));
+    }
 }
 
 mod fizz_buzz {
@@ -316,9 +534,13 @@ mod fizz_buzz {
     test_emit!(short_color);
     test_emit!(rich_no_color);
     test_emit!(short_no_color);
+    test_emit!(rustc_no_color);
+    test_emit!(gcc_no_color);
+    test_emit!(vim_no_color);
+    test_emit!(emacs_no_color);
 }
 
-mod tabbed {
+mod context_lines {
     use super::*;
 
     lazy_static::lazy_static! {
@@ -326,27 +548,124 @@ mod tabbed {
             let mut files = SimpleFiles::new();
 
             let file_id = files.add(
-                "tabbed",
+                "Colors.fun",
+                unindent::unindent(
+                    r#"
+                        module Colors where
+
+                        data Color =
+                            | Red
+                            | Green
+                            | Blue
+
+                        describe : Color -> String
+                        describe color =
+                            case color of
+                                Red => "warm"
+                                Green => "fresh"
+                                Blue => 42
+                                Purple => "royal"
+                                _ => "unknown"
+
+                        main : IO ()
+                        main = print (describe Red)
+                    "#,
+                ),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::error()
+                    .with_message("`case` clauses have incompatible types")
+                    .with_code("E0308")
+                    .with_labels(vec![
+                        Label::primary(file_id, 193..195).with_message("expected `String`, found `Int`"),
+                        Label::secondary(file_id, 88..94).with_message("expected due to this return type"),
+                        Label::secondary(file_id, 116..244).with_message("`case` clauses have incompatible types"),
+                    ]),
+                Diagnostic::error()
+                    .with_message("unknown constructor `Purple`")
+                    .with_labels(vec![
+                        Label::primary(file_id, 204..210).with_message("not a constructor of `Color`"),
+                        Label::secondary(file_id, 26..31).with_message("`Color` is defined here"),
+                    ]),
+                Diagnostic::note()
+                    .with_message("`describe` could be written as a lookup table")
+                    .with_labels(vec![
+                        Label::secondary(file_id, 68..244).with_message("this function"),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    #[test]
+    fn rich_no_color() {
+        let config = Config {
+            before_label_lines: 1,
+            after_label_lines: 1,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("rich_no_color", TEST_DATA.emit_no_color(&config));
+    }
+
+    #[test]
+    fn rich_color() {
+        let config = Config {
+            before_label_lines: 1,
+            after_label_lines: 1,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("rich_color", TEST_DATA.emit_color(&config));
+    }
+
+    #[test]
+    fn no_collapse_no_color() {
+        let config = Config {
+            max_unlabelled_lines: usize::max_value(),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("no_collapse_no_color", TEST_DATA.emit_no_color(&config));
+    }
+}
+
+mod max_width {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "bundle.min.js",
                 [
-                    "Entity:",
-                    "\tArmament:",
-                    "\t\tWeapon: DogJaw",
-                    "\t\tReloadingCondition:\tattack-cooldown",
-                    "\tFoo: Bar",
+                    "(function(){var a=document.querySelectorAll('.item'),b=[];for(var i=0;i<a.length;i++){b.push(a[i].dataset.id)}var c=b.map(function(d){return d.toUpperCase()}),e=c.filter(function(f){return f.length>3});console.log(e.lenght)})();",
+                    "var config={debug:false,retries:3};",
+                    "",
                 ]
                 .join("\n"),
             );
 
             let diagnostics = vec![
+                Diagnostic::error()
+                    .with_message("no property named `lenght` exists on arrays, although a property with a similar name exists")
+                    .with_code("E0609")
+                    .with_labels(vec![
+                        Label::primary(file_id, 216..222).with_message("unknown property"),
+                        Label::secondary(file_id, 159..160).with_message("`e` is defined here"),
+                    ])
+                    .with_notes(vec![
+                        "help: the property `length` holds the number of elements in the array, which is probably what was intended here".to_owned(),
+                    ]),
                 Diagnostic::warning()
-                    .with_message("unknown weapon `DogJaw`")
-                    .with_labels(vec![Label::primary(file_id, 29..35).with_message("the weapon")]),
-                Diagnostic::warning()
-                    .with_message("unknown condition `attack-cooldown`")
-                    .with_labels(vec![Label::primary(file_id, 58..73).with_message("the condition")]),
-                Diagnostic::warning()
-                    .with_message("unknown field `Foo`")
-                    .with_labels(vec![Label::primary(file_id, 75..78).with_message("the field")]),
+                    .with_message("unused property")
+                    .with_labels(vec![
+                        Label::primary(file_id, 241..246).with_message("never read"),
+                        Label::secondary(file_id, 0..5),
+                    ]),
             ];
 
             TestData { files, diagnostics }
@@ -354,32 +673,818 @@ mod tabbed {
     }
 
     #[test]
-    fn tab_width_default_no_color() {
-        let config = TEST_CONFIG.clone();
+    fn rich_no_color() {
+        let config = Config {
+            max_width: Some(80),
+            ..TEST_CONFIG.clone()
+        };
 
-        insta::assert_snapshot!(
-            "tab_width_default_no_color",
-            TEST_DATA.emit_no_color(&config)
-        );
+        insta::assert_snapshot!("rich_no_color", TEST_DATA.emit_no_color(&config));
     }
 
     #[test]
-    fn tab_width_3_no_color() {
+    fn rich_color() {
         let config = Config {
-            tab_width: 3,
+            max_width: Some(80),
             ..TEST_CONFIG.clone()
         };
 
-        insta::assert_snapshot!("tab_width_3_no_color", TEST_DATA.emit_no_color(&config));
+        insta::assert_snapshot!("rich_color", TEST_DATA.emit_color(&config));
     }
 
     #[test]
-    fn tab_width_6_no_color() {
+    fn short_no_color() {
         let config = Config {
-            tab_width: 6,
+            display_style: DisplayStyle::Short,
+            max_width: Some(80),
             ..TEST_CONFIG.clone()
         };
 
-        insta::assert_snapshot!("tab_width_6_no_color", TEST_DATA.emit_no_color(&config));
+        insta::assert_snapshot!("short_no_color", TEST_DATA.emit_no_color(&config));
+    }
+}
+
+mod unicode {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "unicode.rs",
+                [
+                    "let greeting = \"\u{1F44B}\u{1F3FD} hello\";",
+                    "let family = \"\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\";",
+                    "let name = \"Jose\u{301}\";",
+                    "let 名前 = \"世界\";",
+                    "let flag = \"\u{1F1F3}\u{1F1FF}\";",
+                    "let heart = \"\u{2764}\u{FE0F}\";",
+                    "",
+                ]
+                .join("\n"),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::warning()
+                    .with_message("unused variable")
+                    .with_labels(vec![
+                        Label::primary(file_id, 4..12).with_message("never used"),
+                        Label::secondary(file_id, 15..31).with_message("assigned here"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("greeting is not capitalized")
+                    .with_labels(vec![
+                        Label::primary(file_id, 25..30).with_message("should be `Hello`"),
+                    ]),
+                Diagnostic::error()
+                    .with_message("mismatched types")
+                    .with_labels(vec![
+                        Label::primary(file_id, 46..66).with_message("expected `char`, found `&str`"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("combining character in string literal")
+                    .with_labels(vec![
+                        Label::primary(file_id, 84..86).with_message("combined with the preceding `e`"),
+                        Label::secondary(file_id, 79..87),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("non-ASCII identifier")
+                    .with_labels(vec![
+                        Label::primary(file_id, 93..99).with_message("identifier"),
+                        Label::secondary(file_id, 102..110).with_message("assigned here"),
+                    ]),
+                Diagnostic::note()
+                    .with_message("emoji in string literals")
+                    .with_labels(vec![
+                        Label::primary(file_id, 123..133).with_message("flag made of regional indicators"),
+                        Label::primary(file_id, 147..155).with_message("heart with an emoji presentation selector"),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    test_emit!(rich_no_color);
+    test_emit!(short_no_color);
+
+    #[test]
+    fn rich_graphemes_no_color() {
+        let config = Config {
+            column_unit: ColumnUnit::Grapheme,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("rich_graphemes_no_color", TEST_DATA.emit_no_color(&config));
+    }
+
+    #[test]
+    fn short_graphemes_no_color() {
+        let config = Config {
+            display_style: DisplayStyle::Short,
+            column_unit: ColumnUnit::Grapheme,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("short_graphemes_no_color", TEST_DATA.emit_no_color(&config));
+    }
+}
+
+mod hyperlinks {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "/home/user/my project/src/main.rs",
+                [
+                    "fn main() {",
+                    "    let x: i32 = \"hello\";",
+                    "}",
+                    "",
+                ]
+                .join("\n"),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::error()
+                    .with_message("mismatched types")
+                    .with_code("E0308")
+                    .with_labels(vec![
+                        Label::primary(file_id, 29..36).with_message("expected `i32`, found `&str`"),
+                        Label::secondary(file_id, 23..26).with_message("expected due to this"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("unused file")
+                    .with_labels(vec![Label::primary_file(file_id)]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+
+        static ref RELATIVE_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add("src/lib.rs", "pub fn f() {}\n".to_owned());
+
+            let diagnostics = vec![Diagnostic::warning()
+                .with_message("unused function")
+                .with_labels(vec![Label::primary(file_id, 7..8)])];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    /// Make the escape sequences of the hyperlinks visible in snapshots.
+    fn show_escapes(output: String) -> String {
+        output.replace('\x1b', "\\x1b")
+    }
+
+    #[test]
+    fn rich_color() {
+        let config = Config {
+            hyperlinks: Some(
+                Hyperlinks::new().with_code_template("https://example.com/errors/{code}.html"),
+            ),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("rich_color", show_escapes(TEST_DATA.emit_color(&config)));
+    }
+
+    #[test]
+    fn short_color() {
+        let config = Config {
+            display_style: DisplayStyle::Short,
+            hyperlinks: Some(
+                Hyperlinks::new().with_code_template("https://example.com/errors/{code}.html"),
+            ),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("short_color", show_escapes(TEST_DATA.emit_color(&config)));
+    }
+
+    #[test]
+    fn editor_template_short_color() {
+        let config = Config {
+            display_style: DisplayStyle::Short,
+            hyperlinks: Some(
+                Hyperlinks::new().with_locus_template("vscode://file/{path}:{line}:{col}"),
+            ),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!(
+            "editor_template_short_color",
+            show_escapes(TEST_DATA.emit_color(&config))
+        );
+    }
+
+    #[test]
+    fn rich_no_color() {
+        let config = Config {
+            hyperlinks: Some(
+                Hyperlinks::new().with_code_template("https://example.com/errors/{code}.html"),
+            ),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("rich_no_color", TEST_DATA.emit_no_color(&config));
+    }
+
+    #[test]
+    fn relative_path_short_color() {
+        let config = Config {
+            display_style: DisplayStyle::Short,
+            hyperlinks: Some(Hyperlinks::new().with_base_dir("/home/user/my project")),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!(
+            "relative_path_short_color",
+            show_escapes(RELATIVE_DATA.emit_color(&config))
+        );
+    }
+
+    #[test]
+    fn relative_path_without_base_dir_short_color() {
+        let config = Config {
+            display_style: DisplayStyle::Short,
+            hyperlinks: Some(Hyperlinks::new()),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!(
+            "relative_path_without_base_dir_short_color",
+            show_escapes(RELATIVE_DATA.emit_color(&config))
+        );
+    }
+}
+
+mod escaped_chars {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "trojan.rs",
+                [
+                    "fn main() {",
+                    "    let access_level = \"user\";",
+                    "    if access_level != \"user\u{202E} \u{2066}// Check if admin\u{2069} \u{2066}\" {",
+                    "        println!(\"\x1b[31mYou are an admin.\x1b[0m\");",
+                    "    }",
+                    "    let is\u{200B}_admin = false;",
+                    "}",
+                    "",
+                ]
+                .join("\n"),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::warning()
+                    .with_message("comparison with a string literal that is never equal")
+                    .with_labels(vec![
+                        Label::primary(file_id, 66..103).with_message("this string contains hidden text"),
+                        Label::secondary(file_id, 104..105).with_message("branch taken"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("escape sequence in string literal")
+                    .with_labels(vec![
+                        Label::primary(file_id, 124..129).with_message("this changes the color of the terminal"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("unused variable")
+                    .with_labels(vec![
+                        Label::primary(file_id, 168..179).with_message("never used"),
+                        Label::secondary(file_id, 182..187).with_message("assigned here"),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    #[test]
+    fn pictures_no_color() {
+        let config = Config {
+            escape_style: EscapeStyle::Pictures,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("pictures_no_color", TEST_DATA.emit_no_color(&config));
+    }
+
+    #[test]
+    fn code_points_no_color() {
+        let config = Config {
+            escape_style: EscapeStyle::CodePoints,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("code_points_no_color", TEST_DATA.emit_no_color(&config));
+    }
+
+    #[test]
+    fn code_points_color() {
+        let config = Config {
+            escape_style: EscapeStyle::CodePoints,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("code_points_color", TEST_DATA.emit_color(&config));
+    }
+}
+
+mod highlighting {
+    use super::*;
+
+    use codespan_reporting::term::termcolor::ColorSpec;
+    use codespan_reporting::term::Highlighter;
+    use std::ops::Range;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "main.rs",
+                unindent::unindent(r#"
+                    fn main() {
+                        let greeting = "hello";
+                        let count = greeting + 1;
+                    }
+                "#),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::error()
+                    .with_code("E0369")
+                    .with_message("cannot add `{integer}` to `&str`")
+                    .with_labels(vec![
+                        Label::primary(file_id, 67..68).with_message("expected `&str`, found integer"),
+                        Label::secondary(file_id, 56..64).with_message("`&str`"),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    /// Highlights keywords and string literals.
+    struct TestHighlighter;
+
+    impl Highlighter<usize> for TestHighlighter {
+        fn highlight(
+            &self,
+            file_id: usize,
+            line_range: Range<usize>,
+        ) -> Vec<(Range<usize>, ColorSpec)> {
+            let source = TEST_DATA.files.get(file_id).unwrap().source();
+            let line = &source[line_range.clone()];
+
+            let mut keyword = ColorSpec::new();
+            keyword.set_fg(Some(Color::Magenta)).set_bold(true);
+            let mut string = ColorSpec::new();
+            string.set_fg(Some(Color::Green));
+
+            let mut segments = Vec::new();
+            for name in &["fn", "let"] {
+                for (start, _) in line.match_indices(&format!("{} ", name)) {
+                    let start = line_range.start + start;
+                    segments.push((start..start + name.len(), keyword.clone()));
+                }
+            }
+            if let Some(start) = line.find('"') {
+                let end = start + 1 + line[start + 1..].find('"').unwrap();
+                segments.push((line_range.start + start..line_range.start + end + 1, string));
+            }
+            segments
+        }
+    }
+
+    #[test]
+    fn rich_color() {
+        let config = Config {
+            before_label_lines: 2,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!(
+            "rich_color",
+            TEST_DATA.emit_highlighted_color(&config, &TestHighlighter)
+        );
+    }
+
+    #[test]
+    fn rich_no_color() {
+        let config = Config {
+            before_label_lines: 2,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!(
+            "rich_no_color",
+            TEST_DATA.emit_highlighted_no_color(&config, &TestHighlighter)
+        );
+    }
+
+    #[test]
+    fn gcc_color() {
+        let config = Config {
+            display_style: DisplayStyle::Gcc,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!(
+            "gcc_color",
+            TEST_DATA.emit_highlighted_color(&config, &TestHighlighter)
+        );
+    }
+}
+
+mod children {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "Test.fun",
+                unindent::unindent(
+                    "
+                        module Test where
+
+                        foo : Nat
+                        foo = 1
+
+                        bar : Nat
+                        bar = foo
+
+                        foo : Nat
+                        foo = 2
+                    ",
+                ),
+            );
+
+            let diagnostics = vec![
+                // Child diagnostic with a label
+                Diagnostic::error()
+                    .with_message("the name `foo` is defined multiple times")
+                    .with_code("E0428")
+                    .with_labels(vec![Label::primary(file_id, 59..62).with_message("`foo` redefined here")])
+                    .with_notes(vec!["`foo` must be defined only once in this module".to_owned()])
+                    .with_children(vec![
+                        SubDiagnostic::note()
+                            .with_message("the previous definition of `foo` is here")
+                            .with_labels(vec![
+                                Label::primary(file_id, 19..22).with_message("previous type signature"),
+                                Label::secondary(file_id, 29..32),
+                            ]),
+                    ]),
+                // Child diagnostic without a label
+                Diagnostic::warning()
+                    .with_message("unused definition: `bar`")
+                    .with_labels(vec![Label::primary(file_id, 48..51).with_message("never used")])
+                    .with_children(vec![
+                        SubDiagnostic::help().with_message("consider removing the definition"),
+                    ]),
+                // Diagnostic without a label, with a child diagnostic with a label
+                Diagnostic::note()
+                    .with_message("2 definitions of `foo` in this module")
+                    .with_children(vec![
+                        SubDiagnostic::note()
+                            .with_message("the first definition of `foo` is here")
+                            .with_labels(vec![Label::primary(file_id, 19..22)]),
+                        SubDiagnostic::help().with_message("consider renaming one of the definitions"),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    test_emit!(rich_color);
+    test_emit!(short_color);
+    test_emit!(rich_no_color);
+    test_emit!(short_no_color);
+    test_emit!(rustc_no_color);
+    test_emit!(gcc_no_color);
+    test_emit!(vim_no_color);
+    test_emit!(emacs_no_color);
+
+    /// Whether a line matches `%f:%l:%m` in Vim's default `errorformat`,
+    /// which also covers `%f:%l:%c:%m`.
+    fn matches_errorformat(line: &str) -> bool {
+        let mut parts = line.splitn(3, ':');
+        let file = parts.next().unwrap_or("");
+        let line_number = parts.next().unwrap_or("");
+        !file.is_empty()
+            && !line_number.is_empty()
+            && line_number.bytes().all(|byte| byte.is_ascii_digit())
+            && parts.next().is_some()
+    }
+
+    #[test]
+    fn vim_errorformat() {
+        let config = Config {
+            display_style: DisplayStyle::Vim,
+            ..TEST_CONFIG.clone()
+        };
+        let output = TEST_DATA.emit_no_color(&config);
+
+        // Continuation lines are indented, and every other line is located
+        for line in output.lines().filter(|line| !line.starts_with(' ')) {
+            assert!(matches_errorformat(line), "unmatched line: {:?}", line);
+        }
+    }
+}
+
+mod suggestions {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "Test.fun",
+                unindent::unindent(
+                    "
+                        module Test where
+
+                        import Data.Nat
+                        import Data.Nat
+
+                        {-# BUILTIN NATRAL Nat #-}
+
+                        double : Nat → Nat
+                        double n = n + n
+
+                        main = double 2 3
+                    ",
+                ),
+            );
+
+            let diagnostics = vec![
+                // Replacement
+                Diagnostic::error()
+                    .with_message("unknown builtin: `NATRAL`")
+                    .with_labels(vec![Label::primary(file_id, 64..70).with_message("unknown builtin")])
+                    .with_suggestions(vec![
+                        Suggestion::new(file_id, 64..70, "NATURAL")
+                            .with_message("there is a builtin with a similar name")
+                            .with_applicability(Applicability::MachineApplicable),
+                    ]),
+                // Removal of a whole line
+                Diagnostic::warning()
+                    .with_message("duplicate import of `Data.Nat`")
+                    .with_labels(vec![
+                        Label::primary(file_id, 35..50).with_message("duplicate import"),
+                        Label::secondary(file_id, 19..34).with_message("previously imported here"),
+                    ])
+                    .with_suggestions(vec![
+                        Suggestion::new(file_id, 35..51, "")
+                            .with_message("remove the duplicate import")
+                            .with_applicability(Applicability::MachineApplicable),
+                    ]),
+                // Removal and multi-line insertion
+                Diagnostic::error()
+                    .with_message("function takes 1 argument but 2 arguments were supplied")
+                    .with_labels(vec![Label::primary(file_id, 119..136).with_message("expected 1 argument")])
+                    .with_suggestions(vec![
+                        Suggestion::new(file_id, 134..136, "")
+                            .with_message("remove the extra argument")
+                            .with_applicability(Applicability::MaybeIncorrect),
+                        Suggestion::new(file_id, 119..119, "main : Nat\n")
+                            .with_message("add a type annotation")
+                            .with_applicability(Applicability::HasPlaceholders),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    test_emit!(rich_color);
+    test_emit!(short_color);
+    test_emit!(rich_no_color);
+    test_emit!(short_no_color);
+    test_emit!(rustc_no_color);
+    test_emit!(gcc_no_color);
+    test_emit!(vim_no_color);
+    test_emit!(emacs_no_color);
+}
+
+mod explanations {
+    use super::*;
+
+    const EXPLANATION: &str = r#"# E0001: unexpected type
+
+An expression was found with a type that differs from the type that was
+expected, for example when applying `+` to a `String`:
+
+```
+_ = 123 + "hello"
+```
+
+Make sure that the operands of `+` are both of type `Nat`.
+"#;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, &'static str>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add("Test.fun", "module Test\n\n_ = 123 + \"hello\"\n");
+
+            let diagnostics = vec![
+                Diagnostic::error()
+                    .with_message("unexpected type in application of `_+_`")
+                    .with_code("E0001")
+                    .with_labels(vec![
+                        Label::primary(file_id, 23..30).with_message("expected `Nat`, found `String`"),
+                    ]),
+                Diagnostic::warning()
+                    .with_message("unused binding")
+                    .with_code("W0001")
+                    .with_labels(vec![Label::primary(file_id, 13..14)]),
+                Diagnostic::error().with_message("no explanation without a code"),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    #[test]
+    fn explain_hint_no_color() {
+        let config = Config {
+            explain_command: Some("fun --explain".to_owned()),
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("explain_hint_no_color", TEST_DATA.emit_no_color(&config));
+    }
+
+    #[test]
+    fn explanation_color() {
+        let config = TEST_CONFIG.clone();
+
+        insta::assert_snapshot!(
+            "explanation_color",
+            support::explanation_color(&config, EXPLANATION)
+        );
+    }
+
+    #[test]
+    fn explanation_no_color() {
+        let config = TEST_CONFIG.clone();
+
+        insta::assert_snapshot!(
+            "explanation_no_color",
+            support::explanation_no_color(&config, EXPLANATION)
+        );
+    }
+}
+
+mod tabbed {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "tabbed",
+                [
+                    "Entity:",
+                    "\tArmament:",
+                    "\t\tWeapon: DogJaw",
+                    "\t\tReloadingCondition:\tattack-cooldown",
+                    "\tFoo: Bar",
+                ]
+                .join("\n"),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::warning()
+                    .with_message("unknown weapon `DogJaw`")
+                    .with_labels(vec![Label::primary(file_id, 29..35).with_message("the weapon")]),
+                Diagnostic::warning()
+                    .with_message("unknown condition `attack-cooldown`")
+                    .with_labels(vec![Label::primary(file_id, 58..73).with_message("the condition")]),
+                Diagnostic::warning()
+                    .with_message("unknown field `Foo`")
+                    .with_labels(vec![Label::primary(file_id, 75..78).with_message("the field")]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    #[test]
+    fn tab_width_default_no_color() {
+        let config = TEST_CONFIG.clone();
+
+        insta::assert_snapshot!(
+            "tab_width_default_no_color",
+            TEST_DATA.emit_no_color(&config)
+        );
+    }
+
+    #[test]
+    fn tab_width_3_no_color() {
+        let config = Config {
+            tab_width: 3,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("tab_width_3_no_color", TEST_DATA.emit_no_color(&config));
+    }
+
+    #[test]
+    fn tab_width_6_no_color() {
+        let config = Config {
+            tab_width: 6,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!("tab_width_6_no_color", TEST_DATA.emit_no_color(&config));
+    }
+}
+
+mod mixed_indentation {
+    use super::*;
+
+    lazy_static::lazy_static! {
+        static ref TEST_DATA: TestData<'static, SimpleFiles<&'static str, String>> = {
+            let mut files = SimpleFiles::new();
+
+            let file_id = files.add(
+                "mixed.rs",
+                [
+                    "fn main() {",
+                    "    let x = 1;",
+                    "  \tlet y = 2;",
+                    "\t  let z = x +\ty;",
+                    "}",
+                    "",
+                ]
+                .join("\n"),
+            );
+
+            let diagnostics = vec![
+                Diagnostic::warning()
+                    .with_message("unused variable")
+                    .with_labels(vec![
+                        Label::primary(file_id, 48..49).with_message("never used"),
+                        Label::secondary(file_id, 52..57).with_message("assigned here"),
+                    ]),
+                Diagnostic::note()
+                    .with_message("variable defined here")
+                    .with_labels(vec![
+                        Label::primary(file_id, 34..35),
+                        Label::secondary(file_id, 56..57).with_message("used here"),
+                    ]),
+            ];
+
+            TestData { files, diagnostics }
+        };
+    }
+
+    test_emit!(rich_no_color);
+    test_emit!(short_no_color);
+
+    #[test]
+    fn display_columns_rich_no_color() {
+        let config = Config {
+            report_display_columns: true,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!(
+            "display_columns_rich_no_color",
+            TEST_DATA.emit_no_color(&config)
+        );
+    }
+
+    #[test]
+    fn display_columns_short_no_color() {
+        let config = Config {
+            display_style: DisplayStyle::Short,
+            report_display_columns: true,
+            ..TEST_CONFIG.clone()
+        };
+
+        insta::assert_snapshot!(
+            "display_columns_short_no_color",
+            TEST_DATA.emit_no_color(&config)
+        );
     }
 }
@@ -0,0 +1,38 @@
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use codespan_reporting::files::SimpleFiles;
+use codespan_reporting::xml;
+
+mod support;
+
+use self::support::{TestData, TEST_FILE_ID};
+
+lazy_static::lazy_static! {
+    static ref TEST_DATA: TestData<'static, SimpleFiles<String, String>> = {
+        let mut data = support::report_data("Test & <Data>/");
+        data.diagnostics.extend(vec![
+            // Compiler bug in a file-wide label
+            Diagnostic::bug()
+                .with_message("failed to \"lower\" module <Test>")
+                .with_labels(vec![Label::primary_file(TEST_FILE_ID)]),
+            // Diagnostic without labels
+            Diagnostic::note().with_message("2 errors in 2 files"),
+        ]);
+        data
+    };
+}
+
+#[test]
+fn checkstyle_multifile() {
+    let mut output = Vec::new();
+    xml::emit_checkstyle(&mut output, &TEST_DATA.files, &TEST_DATA.diagnostics).unwrap();
+
+    insta::assert_snapshot!("checkstyle_multifile", String::from_utf8(output).unwrap());
+}
+
+#[test]
+fn junit_multifile() {
+    let mut output = Vec::new();
+    xml::emit_junit(&mut output, &TEST_DATA.files, &TEST_DATA.diagnostics).unwrap();
+
+    insta::assert_snapshot!("junit_multifile", String::from_utf8(output).unwrap());
+}
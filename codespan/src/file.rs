@@ -122,7 +122,7 @@ where
     /// refer to it again.
     pub fn add(&mut self, name: impl Into<OsString>, source: Source) -> FileId {
         let file_id = FileId::new(self.files.len());
-        self.files.push(File::new(name.into(), source.into()));
+        self.files.push(File::new(name.into(), source));
         file_id
     }
 
@@ -131,7 +131,7 @@ where
     /// This will mean that any outstanding byte indexes will now point to
     /// invalid locations.
     pub fn update(&mut self, file_id: FileId, source: Source) {
-        self.get_mut(file_id).update(source.into())
+        self.get_mut(file_id).update(source)
     }
 
     /// Get a the source file using the file id.
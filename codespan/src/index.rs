@@ -13,7 +13,7 @@ pub type RawIndex = u32;
 pub type RawOffset = i64;
 
 /// A zero-indexed line offset into a source file
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
 pub struct LineIndex(pub RawIndex);
 
@@ -36,12 +36,6 @@ impl LineIndex {
     }
 }
 
-impl Default for LineIndex {
-    fn default() -> LineIndex {
-        LineIndex(0)
-    }
-}
-
 impl fmt::Debug for LineIndex {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "LineIndex(")?;
@@ -83,16 +77,10 @@ impl fmt::Display for LineNumber {
 }
 
 /// A line offset in a source file
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
 pub struct LineOffset(pub RawOffset);
 
-impl Default for LineOffset {
-    fn default() -> LineOffset {
-        LineOffset(0)
-    }
-}
-
 impl fmt::Debug for LineOffset {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "LineOffset(")?;
@@ -108,7 +96,7 @@ impl fmt::Display for LineOffset {
 }
 
 /// A zero-indexed column offset into a source file
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
 pub struct ColumnIndex(pub RawIndex);
 
@@ -131,12 +119,6 @@ impl ColumnIndex {
     }
 }
 
-impl Default for ColumnIndex {
-    fn default() -> ColumnIndex {
-        ColumnIndex(0)
-    }
-}
-
 impl fmt::Debug for ColumnIndex {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "ColumnIndex(")?;
@@ -171,16 +153,10 @@ impl fmt::Display for ColumnNumber {
 }
 
 /// A column offset in a source file
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
 pub struct ColumnOffset(pub RawOffset);
 
-impl Default for ColumnOffset {
-    fn default() -> ColumnOffset {
-        ColumnOffset(0)
-    }
-}
-
 impl fmt::Debug for ColumnOffset {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "ColumnOffset(")?;
@@ -196,7 +172,7 @@ impl fmt::Display for ColumnOffset {
 }
 
 /// A byte position in a source file.
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serialization", derive(Deserialize, Serialize))]
 pub struct ByteIndex(pub RawIndex);
 
@@ -207,12 +183,6 @@ impl ByteIndex {
     }
 }
 
-impl Default for ByteIndex {
-    fn default() -> ByteIndex {
-        ByteIndex(0)
-    }
-}
-
 impl fmt::Debug for ByteIndex {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "ByteIndex(")?;